@@ -5,6 +5,32 @@ pub mod modules;
 use modules::commands;
 use modules::process;
 use modules::files;
+use modules::fees;
+use modules::coin_control;
+use modules::coin_selection;
+use modules::consolidation;
+use modules::raw_tx;
+use modules::multisig;
+use modules::watch_only;
+use modules::wallet_dump;
+use modules::seed_backup;
+use modules::rescan;
+use modules::message;
+use modules::backups;
+use modules::safety_backup;
+use modules::wallet_session;
+use modules::wallets;
+use modules::restricted;
+use modules::qualifiers;
+use modules::verifier;
+use modules::rewards;
+use modules::snapshots;
+use modules::messaging;
+use modules::transfers;
+use modules::naming;
+use modules::burns;
+use modules::minting;
+use tauri::Manager;
 
 
 
@@ -22,8 +48,21 @@ pub fn run() {
             .build(),
         )?;
       }
+      backups::start_backup_scheduler();
+      messaging::start_message_watcher(app.handle().clone());
       Ok(())
     })
+    .on_window_event(|window, event| {
+      // Relock an unlocked wallet when the window is minimized
+      if let tauri::WindowEvent::Resized(_) = event {
+        if window.is_minimized().unwrap_or(false) {
+          let handle = window.app_handle().clone();
+          std::thread::spawn(move || {
+            let _ = wallet_session::lock_for(&handle, "minimized");
+          });
+        }
+      }
+    })
     .invoke_handler(tauri::generate_handler![
       // Commands from modules::commands
       commands::list_utxos,
@@ -42,6 +81,7 @@ pub fn run() {
       commands::unban_peer,
       commands::dump_priv_key,
       commands::import_priv_key,
+      commands::import_priv_keys,
       commands::wallet_encrypt,
       commands::wallet_unlock,
       commands::wallet_lock,
@@ -92,6 +132,160 @@ pub fn run() {
       files::get_data_folder_info,
       files::load_app_settings,
       files::save_app_settings,
+
+      // Commands from modules::fees
+      fees::estimate_fee_rate,
+      fees::estimate_tx_fee,
+      fees::preview_advanced_transaction,
+
+      // Commands from modules::coin_control
+      coin_control::lock_utxos,
+      coin_control::unlock_utxos,
+      coin_control::list_locked_utxos,
+      coin_control::apply_frozen_coins,
+
+      // Commands from modules::coin_selection
+      coin_selection::select_coins,
+
+      // Commands from modules::consolidation
+      consolidation::plan_consolidation,
+      consolidation::start_consolidation,
+
+      // Commands from modules::raw_tx
+      raw_tx::create_unsigned_transaction,
+      raw_tx::load_transaction_file,
+      raw_tx::decode_transaction,
+      raw_tx::sign_transaction_file,
+      raw_tx::test_transaction,
+      raw_tx::broadcast_signed_transaction,
+      raw_tx::broadcast_transaction_file,
+
+      // Commands from modules::multisig
+      multisig::create_multisig_address,
+      multisig::list_multisig_wallets,
+      multisig::remove_multisig_wallet,
+      multisig::get_multisig_balance,
+      multisig::create_multisig_spend,
+      multisig::list_multisig_txs,
+      multisig::sign_multisig_tx,
+      multisig::combine_multisig_tx,
+      multisig::export_multisig_tx,
+      multisig::import_multisig_signatures,
+      multisig::broadcast_multisig_tx,
+      multisig::discard_multisig_tx,
+
+      // Commands from modules::watch_only
+      watch_only::add_watch_only,
+      watch_only::remove_watch_only,
+      watch_only::list_watch_only,
+      watch_only::get_portfolio,
+      // Commands from modules::wallet_dump
+      wallet_dump::export_wallet_dump,
+      wallet_dump::import_wallet_dump,
+      // Commands from modules::seed_backup
+      seed_backup::get_master_key_info,
+      seed_backup::get_seed_words,
+      seed_backup::confirm_seed_backup,
+      seed_backup::get_seed_backup_status,
+      // Commands from modules::rescan
+      rescan::start_rescan,
+      rescan::abort_rescan,
+      rescan::get_rescan_status,
+      // Commands from modules::message
+      message::sign_message,
+      message::sign_message_with_privkey,
+      message::verify_message,
+      message::export_signed_messages,
+      message::parse_signed_messages,
+      message::verify_signed_messages,
+      // Commands from modules::backups
+      backups::get_backup_config,
+      backups::save_backup_settings,
+      backups::generate_backup_key,
+      backups::create_encrypted_backup,
+      backups::list_encrypted_backups,
+      backups::verify_backup,
+      // Commands from modules::safety_backup
+      safety_backup::list_safety_backups,
+      safety_backup::restore_safety_backup,
+      safety_backup::delete_safety_backup,
+      // Commands from modules::wallet_session
+      wallet_session::get_wallet_session,
+      wallet_session::touch_wallet_session,
+      wallet_session::lock_wallet_session,
+      wallet_session::get_staking_unlock_supported,
+      // Commands from modules::wallets
+      wallets::list_wallet_files,
+      wallets::create_wallet,
+      wallets::load_wallet,
+      wallets::unload_wallet,
+      wallets::switch_wallet,
+      wallets::set_wallet_label,
+      // Commands from modules::restricted
+      restricted::issue_restricted_asset,
+      restricted::reissue_restricted_asset,
+      restricted::freeze_address,
+      restricted::unfreeze_address,
+      restricted::freeze_restricted_asset,
+      restricted::unfreeze_restricted_asset,
+      restricted::get_restricted_asset_info,
+      restricted::list_global_restrictions,
+      restricted::list_address_restrictions,
+      restricted::list_my_restricted_addresses,
+      restricted::check_address_restriction,
+      restricted::check_address_eligibility,
+      // Commands from modules::qualifiers
+      qualifiers::issue_qualifier_asset,
+      qualifiers::transfer_qualifier,
+      qualifiers::tag_addresses,
+      qualifiers::untag_addresses,
+      qualifiers::list_addresses_for_tag,
+      qualifiers::list_tags_for_address,
+      qualifiers::check_address_tag,
+      qualifiers::list_my_tagged_addresses,
+      // Commands from modules::verifier
+      verifier::check_verifier_string,
+      verifier::evaluate_verifier,
+      // Commands from modules::rewards
+      rewards::schedule_reward,
+      rewards::list_reward_plans,
+      rewards::get_snapshot_holders,
+      rewards::preview_reward,
+      rewards::execute_reward,
+      rewards::cancel_reward,
+      rewards::remove_reward,
+      rewards::list_snapshot_requests,
+      // Commands from modules::snapshots
+      snapshots::request_holder_snapshot,
+      snapshots::analyze_snapshot,
+      snapshots::get_holder_history,
+      snapshots::export_snapshot_csv,
+      snapshots::purge_snapshot,
+      // Commands from modules::messaging
+      messaging::get_asset_messages,
+      messaging::mark_messages_read,
+      messaging::clear_asset_messages,
+      messaging::list_message_channels,
+      messaging::subscribe_channel,
+      messaging::unsubscribe_channel,
+      messaging::subscribe_held_asset_channels,
+      messaging::send_asset_message,
+      // Commands from modules::transfers
+      transfers::batch_transfer_asset,
+      // Commands from modules::naming
+      naming::validate_asset_name,
+      naming::validate_ipfs_hash,
+      // Commands from modules::burns
+      burns::quote_issuance,
+      burns::get_burn_report,
+      // Commands from modules::minting
+      minting::create_mint_job,
+      minting::list_mint_jobs,
+      minting::get_mint_job,
+      minting::start_mint_job,
+      minting::pause_mint_job,
+      minting::remove_mint_job,
+      minting::export_mint_report,
       
       // Additional Commands
       commands::backup_wallet,
@@ -0,0 +1,395 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::{Read, Write};
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use age::secrecy::{ExposeSecret, SecretString};
+use chrono::{Local, TimeZone};
+use sha2::{Digest, Sha256};
+
+// Import local modules
+use crate::modules::models::{BackupConfig, BackupEntry, BackupKey, BackupManifest, BackupResult, BackupVerification};
+use crate::modules::commands::run_cli;
+use crate::modules::files::{data_dir, ensure_config, wipe_file};
+
+// Encrypted wallet backups. Each backup is `hemp0x_wallet_<ts>.dat.age`
+// plus a `hemp0x_wallet_<ts>.manifest.json` holding SHA-256 sums of the
+// encrypted file and of the wallet inside it. Scheduled backups encrypt to
+// an age recipient (public key) so no secret has to be stored; manual
+// backups may use a passphrase instead.
+
+pub const MANIFEST_VERSION: u32 = 1;
+pub const DEFAULT_INTERVAL_HOURS: u32 = 24;
+pub const DEFAULT_KEEP_DAILY: u32 = 7;
+pub const DEFAULT_KEEP_WEEKLY: u32 = 4;
+
+const BACKUP_PREFIX: &str = "hemp0x_wallet_";
+const BACKUP_SUFFIX: &str = ".dat.age";
+const MANIFEST_SUFFIX: &str = ".manifest.json";
+
+// Berkeley DB btree magic at offset 12, little or big endian
+const BDB_BTREE_MAGIC: u32 = 0x0005_3162;
+
+fn backup_config_path() -> Result<PathBuf, String> {
+  Ok(data_dir()?.join("backup_config.json"))
+}
+
+pub fn load_backup_config() -> Result<BackupConfig, String> {
+  let path = backup_config_path()?;
+  if !path.exists() {
+    return Ok(BackupConfig::default());
+  }
+  let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+  Ok(serde_json::from_str(&content).unwrap_or_default())
+}
+
+fn save_backup_config(config: &BackupConfig) -> Result<(), String> {
+  let path = backup_config_path()?;
+  let content = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+  fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+/// Configured destinations, or `backups/` in the data dir when none are set
+fn destinations(config: &BackupConfig) -> Result<Vec<PathBuf>, String> {
+  let dirs: Vec<PathBuf> = config
+    .destinations
+    .iter()
+    .filter(|d| !d.trim().is_empty())
+    .map(|d| PathBuf::from(d.trim()))
+    .collect();
+  if dirs.is_empty() {
+    return Ok(vec![data_dir()?.join("backups")]);
+  }
+  Ok(dirs)
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+  Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn manifest_path_for(backup: &Path) -> PathBuf {
+  let name = backup.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+  let stem = name.strip_suffix(BACKUP_SUFFIX).unwrap_or(&name);
+  backup.with_file_name(format!("{}{}", stem, MANIFEST_SUFFIX))
+}
+
+fn read_manifest(backup: &Path) -> Option<BackupManifest> {
+  let content = fs::read_to_string(manifest_path_for(backup)).ok()?;
+  serde_json::from_str(&content).ok()
+}
+
+fn looks_like_wallet(data: &[u8]) -> bool {
+  if data.len() < 16 {
+    return false;
+  }
+  let magic = [data[12], data[13], data[14], data[15]];
+  u32::from_le_bytes(magic) == BDB_BTREE_MAGIC || u32::from_be_bytes(magic) == BDB_BTREE_MAGIC
+}
+
+fn encrypt_wallet(plain: &[u8], passphrase: Option<&str>, recipient: Option<&str>) -> Result<(Vec<u8>, String), String> {
+  let (encryptor, method) = match (passphrase.filter(|p| !p.is_empty()), recipient) {
+    (Some(pass), _) => (
+      age::Encryptor::with_user_passphrase(SecretString::from(pass.to_string())),
+      "passphrase",
+    ),
+    (None, Some(r)) => {
+      let recipient: age::x25519::Recipient = r.trim().parse().map_err(|e| format!("Invalid backup recipient: {}", e))?;
+      let encryptor = age::Encryptor::with_recipients(iter::once(&recipient as &dyn age::Recipient))
+        .map_err(|e| e.to_string())?;
+      (encryptor, "recipient")
+    }
+    (None, None) => return Err("Set a backup public key or enter a passphrase to encrypt the backup".to_string()),
+  };
+  let mut encrypted = Vec::new();
+  let mut writer = encryptor.wrap_output(&mut encrypted).map_err(|e| e.to_string())?;
+  writer.write_all(plain).map_err(|e| e.to_string())?;
+  writer.finish().map_err(|e| e.to_string())?;
+  Ok((encrypted, method.to_string()))
+}
+
+fn decrypt_backup(encrypted: &[u8], passphrase: Option<&str>, identity: Option<&str>) -> Result<Vec<u8>, String> {
+  let decryptor = age::Decryptor::new(encrypted).map_err(|e| format!("Not an age-encrypted backup: {}", e))?;
+  let mut reader = if decryptor.is_scrypt() {
+    let pass = passphrase.filter(|p| !p.is_empty()).ok_or("This backup needs its passphrase")?;
+    let identity = age::scrypt::Identity::new(SecretString::from(pass.to_string()));
+    decryptor
+      .decrypt(iter::once(&identity as &dyn age::Identity))
+      .map_err(|_| "Wrong passphrase for this backup".to_string())?
+  } else {
+    let key = identity.filter(|k| !k.trim().is_empty()).ok_or("This backup needs the backup secret key")?;
+    let identity: age::x25519::Identity = key.trim().parse().map_err(|e| format!("Invalid backup secret key: {}", e))?;
+    decryptor
+      .decrypt(iter::once(&identity as &dyn age::Identity))
+      .map_err(|_| "This secret key does not match the backup".to_string())?
+  };
+  let mut plain = Vec::new();
+  reader.read_to_end(&mut plain).map_err(|e| e.to_string())?;
+  Ok(plain)
+}
+
+/// Backups in one destination, newest first
+fn backups_in(dir: &Path) -> Vec<BackupEntry> {
+  let mut entries = Vec::new();
+  let read = match fs::read_dir(dir) {
+    Ok(r) => r,
+    Err(_) => return entries,
+  };
+  for entry in read.flatten() {
+    let path = entry.path();
+    let name = entry.file_name().to_string_lossy().to_string();
+    if !name.starts_with(BACKUP_PREFIX) || !name.ends_with(BACKUP_SUFFIX) {
+      continue;
+    }
+    let meta = entry.metadata().ok();
+    let manifest = read_manifest(&path);
+    let modified = meta
+      .as_ref()
+      .and_then(|m| m.modified().ok())
+      .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+      .map(|d| d.as_secs())
+      .unwrap_or(0);
+    entries.push(BackupEntry {
+      path: path.to_string_lossy().to_string(),
+      destination: dir.to_string_lossy().to_string(),
+      created: manifest.as_ref().map(|m| m.created).unwrap_or(modified),
+      size: meta.map(|m| m.len()).unwrap_or(0),
+      method: manifest.as_ref().map(|m| m.method.clone()),
+      has_manifest: manifest.is_some(),
+    });
+  }
+  entries.sort_by_key(|e| std::cmp::Reverse(e.created));
+  entries
+}
+
+/// Keep the newest backup of each of the last `keep_daily` days and of each
+/// of the last `keep_weekly` ISO weeks; delete the rest. Returns how many
+/// backups were removed.
+pub fn rotate(dir: &Path, keep_daily: u32, keep_weekly: u32) -> u64 {
+  let mut days = HashSet::new();
+  let mut weeks = HashSet::new();
+  let mut removed = 0;
+  for entry in backups_in(dir) {
+    let date = match Local.timestamp_opt(entry.created as i64, 0).single() {
+      Some(d) => d,
+      None => continue,
+    };
+    let day = date.format("%Y-%m-%d").to_string();
+    let week = date.format("%G-W%V").to_string();
+    let mut keep = false;
+    if !days.contains(&day) && (days.len() as u32) < keep_daily.max(1) {
+      days.insert(day);
+      keep = true;
+    }
+    if !weeks.contains(&week) && (weeks.len() as u32) < keep_weekly {
+      weeks.insert(week);
+      keep = true;
+    }
+    if !keep {
+      let path = PathBuf::from(&entry.path);
+      let _ = fs::remove_file(manifest_path_for(&path));
+      if fs::remove_file(&path).is_ok() {
+        removed += 1;
+      }
+    }
+  }
+  removed
+}
+
+/// Write one encrypted backup (and its manifest) to every destination,
+/// check what was written, then rotate each destination
+pub fn create_backup(passphrase: Option<&str>) -> Result<BackupResult, String> {
+  let mut config = load_backup_config()?;
+  let now = Local::now();
+  let ts = now.format("%Y%m%d_%H%M%S").to_string();
+
+  // backupwallet gives a consistent copy even while the node is writing
+  let temp = data_dir()?.join(format!("backup_{}.tmp", ts));
+  run_cli(&[String::from("backupwallet"), temp.to_string_lossy().to_string()])?;
+  let plain = fs::read(&temp).map_err(|e| e.to_string());
+  wipe_file(&temp)?;
+  let plain = plain?;
+
+  let (encrypted, method) = encrypt_wallet(&plain, passphrase, config.recipient.as_deref())?;
+  let file = format!("{}{}{}", BACKUP_PREFIX, ts, BACKUP_SUFFIX);
+  let manifest = BackupManifest {
+    version: MANIFEST_VERSION,
+    file: file.clone(),
+    created: now.timestamp() as u64,
+    recipient: if method == "recipient" { config.recipient.clone() } else { None },
+    method,
+    size: encrypted.len() as u64,
+    sha256: sha256_hex(&encrypted),
+    plain_sha256: sha256_hex(&plain),
+  };
+  let manifest_json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+
+  let mut files = Vec::new();
+  let mut errors = Vec::new();
+  let mut rotated = 0;
+  for dir in destinations(&config)? {
+    let dest = dir.join(&file);
+    let written = fs::create_dir_all(&dir)
+      .and_then(|_| fs::write(&dest, &encrypted))
+      .and_then(|_| fs::write(manifest_path_for(&dest), &manifest_json))
+      .map_err(|e| e.to_string())
+      .and_then(|_| {
+        // Read back so a full disk or flaky share does not go unnoticed
+        let check = fs::read(&dest).map_err(|e| e.to_string())?;
+        if sha256_hex(&check) != manifest.sha256 {
+          return Err("checksum mismatch after write".to_string());
+        }
+        Ok(())
+      });
+    match written {
+      Ok(()) => {
+        files.push(dest.to_string_lossy().to_string());
+        rotated += rotate(
+          &dir,
+          config.keep_daily.unwrap_or(DEFAULT_KEEP_DAILY),
+          config.keep_weekly.unwrap_or(DEFAULT_KEEP_WEEKLY),
+        );
+      }
+      Err(e) => errors.push(format!("{}: {}", dir.display(), e)),
+    }
+  }
+  if files.is_empty() {
+    return Err(format!("Backup could not be written: {}", errors.join("; ")));
+  }
+
+  config.last_backup = Some(manifest.created);
+  save_backup_config(&config)?;
+  Ok(BackupResult {
+    created: manifest.created,
+    files,
+    sha256: manifest.sha256,
+    rotated,
+    errors,
+  })
+}
+
+/// Take a backup whenever the configured interval has passed. Only
+/// recipient-encrypted schedules run unattended.
+pub fn start_backup_scheduler() {
+  thread::spawn(|| loop {
+    thread::sleep(Duration::from_secs(600));
+    let config = match load_backup_config() {
+      Ok(c) => c,
+      Err(_) => continue,
+    };
+    if !config.enabled || config.recipient.is_none() {
+      continue;
+    }
+    let interval = config.interval_hours.unwrap_or(DEFAULT_INTERVAL_HOURS).max(1) as u64 * 3600;
+    let now = Local::now().timestamp() as u64;
+    if now.saturating_sub(config.last_backup.unwrap_or(0)) < interval {
+      continue;
+    }
+    // Skip quietly while the node is down; the next tick tries again
+    if run_cli(&[String::from("getwalletinfo")]).is_err() {
+      continue;
+    }
+    match create_backup(None) {
+      Ok(result) => {
+        for e in result.errors {
+          log::warn!("Scheduled backup destination failed: {}", e);
+        }
+      }
+      Err(e) => log::warn!("Scheduled backup failed: {}", e),
+    }
+  });
+}
+
+#[tauri::command]
+pub fn get_backup_config() -> Result<BackupConfig, String> {
+  load_backup_config()
+}
+
+#[tauri::command]
+pub fn save_backup_settings(config: BackupConfig) -> Result<(), String> {
+  if let Some(r) = config.recipient.as_deref().filter(|r| !r.trim().is_empty()) {
+    r.trim()
+      .parse::<age::x25519::Recipient>()
+      .map_err(|e| format!("Invalid backup recipient: {}", e))?;
+  }
+  // last_backup is owned by the backup run, not the settings form
+  let mut config = config;
+  config.recipient = config.recipient.filter(|r| !r.trim().is_empty()).map(|r| r.trim().to_string());
+  config.last_backup = load_backup_config()?.last_backup;
+  save_backup_config(&config)
+}
+
+/// New age key pair for scheduled backups. Only the public half is kept by
+/// the commander; the secret key must be stored by the user to restore.
+#[tauri::command]
+pub fn generate_backup_key() -> Result<BackupKey, String> {
+  let identity = age::x25519::Identity::generate();
+  Ok(BackupKey {
+    identity: identity.to_string().expose_secret().to_string(),
+    recipient: identity.to_public().to_string(),
+  })
+}
+
+#[tauri::command]
+pub fn create_encrypted_backup(passphrase: Option<String>) -> Result<BackupResult, String> {
+  ensure_config()?;
+  create_backup(passphrase.as_deref())
+}
+
+#[tauri::command]
+pub fn list_encrypted_backups() -> Result<Vec<BackupEntry>, String> {
+  let config = load_backup_config()?;
+  let mut entries: Vec<BackupEntry> = destinations(&config)?.iter().flat_map(|d| backups_in(d)).collect();
+  entries.sort_by_key(|e| std::cmp::Reverse(e.created));
+  Ok(entries)
+}
+
+/// Check a backup end to end: checksum against the manifest, decryption,
+/// the decrypted wallet against the manifest, and the wallet file format
+#[tauri::command]
+pub fn verify_backup(path: String, passphrase: Option<String>, identity: Option<String>) -> Result<BackupVerification, String> {
+  let backup = PathBuf::from(path.trim());
+  let encrypted = fs::read(&backup).map_err(|e| e.to_string())?;
+  let manifest = read_manifest(&backup);
+  let mut errors = Vec::new();
+
+  let checksum_ok = manifest.as_ref().map(|m| {
+    let ok = sha256_hex(&encrypted) == m.sha256 && encrypted.len() as u64 == m.size;
+    if !ok {
+      errors.push("Encrypted file does not match its manifest".to_string());
+    }
+    ok
+  });
+  if manifest.is_none() {
+    errors.push("No manifest found next to the backup".to_string());
+  }
+
+  let (decrypted, content_ok, wallet_format_ok) =
+    match decrypt_backup(&encrypted, passphrase.as_deref(), identity.as_deref()) {
+      Ok(plain) => {
+        let content_ok = manifest.as_ref().map(|m| sha256_hex(&plain) == m.plain_sha256);
+        if content_ok == Some(false) {
+          errors.push("Decrypted wallet does not match its manifest".to_string());
+        }
+        let format_ok = looks_like_wallet(&plain);
+        if !format_ok {
+          errors.push("Decrypted data is not a wallet.dat file".to_string());
+        }
+        (true, content_ok, format_ok)
+      }
+      Err(e) => {
+        errors.push(e);
+        (false, None, false)
+      }
+    };
+
+  Ok(BackupVerification {
+    path: backup.to_string_lossy().to_string(),
+    checksum_ok,
+    decrypted,
+    content_ok,
+    wallet_format_ok,
+    errors,
+  })
+}
@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use chrono::{Local, TimeZone};
+use uuid::Uuid;
+
+// Import local modules
+use crate::modules::models::{BurnKindTotal, BurnRecord, BurnReport, IssuanceQuote};
+use crate::modules::utils::{to_sats, format_sats};
+use crate::modules::commands::run_cli;
+use crate::modules::files::ensure_config;
+use crate::modules::fees::{estimate_tx_size, fee_for_size, resolve_fee_rate, P2PKH_OUTPUT_SIZE};
+use crate::modules::naming::{check_asset_name, expect_asset_kind, AssetKind};
+
+// Issuing, reissuing and tagging pay a fixed amount of HEMP to a burn
+// address. The node has no dry run for these calls and does not publish
+// the burn amounts, so a quote is an estimate, not a built transaction:
+// the burn is what this wallet paid before (or the cost the issue dialogs
+// show), the network fee comes from the usual size of that transaction.
+// Kinds with neither have an unknown burn; their quote is only usable
+// when the user accepts that explicitly. Every issuance command takes the
+// id of a quote the user confirmed.
+
+/// How long a confirmed quote can be used
+pub const QUOTE_TTL_SECS: u64 = 300;
+
+/// Asset outputs carry the asset script (name, amount, units, IPFS) on top
+/// of the P2PKH script
+const ASSET_SCRIPT_SIZE: u64 = 50;
+const IPFS_SCRIPT_SIZE: u64 = 34;
+
+/// Costs the issue and reissue dialogs have always shown, until the wallet
+/// has paid one
+const KNOWN_BURNS: &[(&str, &str)] = &[
+  ("issue_root", "0.25"),
+  ("issue_sub", "0.56"),
+  ("issue_unique", "5"),
+  ("reissue", "0.25"),
+];
+
+/// Burn addresses are vanity addresses spelling the operation, padded with
+/// X. Longer fragments first: `reissueasset` also contains `issueasset`.
+const BURN_FRAGMENTS: &[(&str, &str)] = &[
+  ("issuesubqualifier", "issue_sub_qualifier"),
+  ("issuequalifier", "issue_qualifier"),
+  ("issuerestricted", "issue_restricted"),
+  ("issuemsgchannel", "issue_channel"),
+  ("issueuniqueasset", "issue_unique"),
+  ("issuesubasset", "issue_sub"),
+  ("reissueasset", "reissue"),
+  ("issueasset", "issue_root"),
+  ("addtag", "add_tag"),
+  ("burn", "burn"),
+];
+
+struct PendingQuote {
+  id: String,
+  operation: String,
+  name: String,
+  count: u32,
+  expires: u64,
+  sufficient: bool,
+  burn_known: bool,
+}
+
+static QUOTES: Mutex<Vec<PendingQuote>> = Mutex::new(Vec::new());
+
+fn now() -> u64 {
+  Local::now().timestamp() as u64
+}
+
+/// Burn kind of an address, `None` for anything that is not a burn address
+pub fn burn_kind(address: &str) -> Option<&'static str> {
+  if !address.contains("XXXXX") {
+    return None;
+  }
+  let lower = address.to_ascii_lowercase();
+  BURN_FRAGMENTS.iter().find(|(fragment, _)| lower.contains(fragment)).map(|(_, kind)| *kind)
+}
+
+/// Burn kind and unit count of an operation. `issue` takes the full name of
+/// the new asset, `issue_unique` the parent and `count` tags, `reissue` the
+/// asset being reissued.
+fn operation_kind(operation: &str, name: &str, count: u32) -> Result<(&'static str, u32), String> {
+  match operation {
+    "issue" => Ok(match check_asset_name(name)? {
+      AssetKind::Root => ("issue_root", 1),
+      AssetKind::Sub => ("issue_sub", 1),
+      AssetKind::Unique => ("issue_unique", 1),
+      AssetKind::Channel => ("issue_channel", 1),
+      AssetKind::Qualifier => ("issue_qualifier", 1),
+      AssetKind::SubQualifier => ("issue_sub_qualifier", 1),
+      AssetKind::Restricted => ("issue_restricted", 1),
+      AssetKind::Owner => return Err("Owner tokens are created with their asset, not issued".to_string()),
+    }),
+    "issue_unique" => {
+      expect_asset_kind(name, &[AssetKind::Root, AssetKind::Sub], "the parent of unique assets")?;
+      if count == 0 {
+        return Err("At least one tag is required".to_string());
+      }
+      Ok(("issue_unique", count))
+    }
+    "reissue" => {
+      expect_asset_kind(name, &[AssetKind::Root, AssetKind::Sub, AssetKind::Restricted], "reissued")?;
+      Ok(("reissue", 1))
+    }
+    _ => Err(format!("Unknown operation '{}'; use issue, issue_unique or reissue", operation)),
+  }
+}
+
+/// Inputs, plain outputs (burn, change) and asset outputs of the transaction
+fn tx_shape(kind: &str, count: u32) -> (usize, usize, u64) {
+  match kind {
+    // HEMP only; the new asset and its owner token
+    "issue_root" => (1, 2, 2),
+    "issue_qualifier" => (1, 2, 1),
+    // The parent's owner token goes in and comes back
+    "issue_sub" => (2, 2, 3),
+    "issue_unique" => (2, 2, count as u64 + 1),
+    _ => (2, 2, 2),
+  }
+}
+
+fn estimate_network_fee(kind: &str, count: u32, has_ipfs: bool, fee_rate: f64) -> u64 {
+  let (inputs, outputs, asset_outputs) = tx_shape(kind, count);
+  let ipfs = if has_ipfs { IPFS_SCRIPT_SIZE * count as u64 } else { 0 };
+  let size = estimate_tx_size(inputs, outputs) + asset_outputs * (P2PKH_OUTPUT_SIZE + ASSET_SCRIPT_SIZE) + ipfs;
+  fee_for_size(size, fee_rate)
+}
+
+/// Every burn output this wallet sent, oldest first
+fn wallet_burns() -> Result<Vec<(BurnRecord, u64, u64)>, String> {
+  let raw = run_cli(&[
+    String::from("listtransactions"),
+    String::from("*"),
+    String::from("100000"),
+    String::from("0"),
+  ])?;
+  let list: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  let mut burns = Vec::new();
+  for tx in list.as_array().map(|v| v.as_slice()).unwrap_or_default() {
+    if tx["category"].as_str() != Some("send") {
+      continue;
+    }
+    let address = tx["address"].as_str().unwrap_or("");
+    let Some(kind) = burn_kind(address) else { continue };
+    let amount = to_sats(tx["amount"].as_f64().unwrap_or(0.0).abs());
+    let fee = to_sats(tx["fee"].as_f64().unwrap_or(0.0).abs());
+    let time = tx["time"].as_i64().unwrap_or(0);
+    let record = BurnRecord {
+      txid: tx["txid"].as_str().unwrap_or("").to_string(),
+      time: Local
+        .timestamp_opt(time, 0)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default(),
+      kind: kind.to_string(),
+      address: address.to_string(),
+      amount: format_sats(amount),
+      fee: format_sats(fee),
+      confirmations: tx["confirmations"].as_i64().unwrap_or(0),
+    };
+    burns.push((record, amount, fee));
+  }
+  Ok(burns)
+}
+
+/// Burn per unit of `kind`: the smallest this wallet paid (batches of
+/// unique assets pay a multiple), else the known cost
+fn burn_per_unit(kind: &str) -> Option<(u64, &'static str)> {
+  let observed = wallet_burns()
+    .ok()
+    .and_then(|burns| burns.iter().filter(|(r, _, _)| r.kind == kind).map(|(_, amount, _)| *amount).min());
+  match observed {
+    Some(amount) => Some((amount, "history")),
+    None => KNOWN_BURNS
+      .iter()
+      .find(|(k, _)| *k == kind)
+      .and_then(|(_, cost)| cost.parse::<f64>().ok())
+      .map(|cost| (to_sats(cost), "default")),
+  }
+}
+
+fn wallet_balance() -> Result<u64, String> {
+  let raw = run_cli(&[String::from("getbalance")])?;
+  raw.trim().parse::<f64>().map(to_sats).map_err(|_| format!("Unexpected node reply: {}", raw))
+}
+
+/// Use up a confirmed quote. Issuance commands call this before sending.
+pub fn take_quote(quote_id: &str, operation: &str, name: &str, count: u32) -> Result<(), String> {
+  let mut quotes = QUOTES.lock().map_err(|_| "Quote store unavailable".to_string())?;
+  let now = now();
+  quotes.retain(|q| q.expires > now);
+  let Some(pos) = quotes.iter().position(|q| q.id == quote_id) else {
+    return Err("Get a cost quote and confirm it before issuing (quotes last 5 minutes)".to_string());
+  };
+  let quote = quotes.remove(pos);
+  if quote.operation != operation || quote.name != name || quote.count != count {
+    return Err(format!(
+      "The confirmed quote was for {} {} ({}), not {} {} ({})",
+      quote.operation, quote.name, quote.count, operation, name, count
+    ));
+  }
+  if !quote.sufficient {
+    return Err(if quote.burn_known {
+      "The wallet balance does not cover the quoted burn and fee".to_string()
+    } else {
+      "The burn of this operation is unknown; accept that in the quote before issuing".to_string()
+    });
+  }
+  Ok(())
+}
+
+/// Estimated burn, network fee and resulting balance of an issuance, plus
+/// the id to pass to the issuance command once the user confirms. A quote
+/// with an unknown burn is never sufficient unless `accept_unknown_burn`.
+#[tauri::command]
+pub fn quote_issuance(
+  operation: String,
+  name: String,
+  count: Option<u32>,
+  has_ipfs: Option<bool>,
+  fee_rate: Option<f64>,
+  conf_target: Option<u32>,
+  accept_unknown_burn: Option<bool>,
+) -> Result<IssuanceQuote, String> {
+  ensure_config()?;
+  let name = name.trim().to_string();
+  let (kind, count) = operation_kind(&operation, &name, count.unwrap_or(1))?;
+  let rate = resolve_fee_rate(fee_rate, conf_target)?;
+  let network_fee = estimate_network_fee(kind, count, has_ipfs.unwrap_or(false), rate.fee_rate);
+  let burn = burn_per_unit(kind);
+  let balance = wallet_balance()?;
+  let cost = burn.map(|(unit, _)| unit * count as u64 + network_fee);
+  let sufficient = match cost {
+    Some(cost) => balance >= cost,
+    None => accept_unknown_burn.unwrap_or(false) && balance >= network_fee,
+  };
+
+  let quote = PendingQuote {
+    id: Uuid::new_v4().to_string(),
+    operation: operation.clone(),
+    name: name.clone(),
+    count,
+    expires: now() + QUOTE_TTL_SECS,
+    sufficient,
+    burn_known: burn.is_some(),
+  };
+  let quote_id = quote.id.clone();
+  let expires = quote.expires;
+  QUOTES.lock().map_err(|_| "Quote store unavailable".to_string())?.push(quote);
+
+  Ok(IssuanceQuote {
+    quote_id,
+    operation,
+    name,
+    kind: kind.to_string(),
+    count,
+    burn: burn.map(|(unit, _)| format_sats(unit * count as u64)),
+    burn_source: burn.map(|(_, source)| source).unwrap_or("unknown").to_string(),
+    network_fee: format_sats(network_fee),
+    fee_rate: rate.fee_rate,
+    balance: format_sats(balance),
+    resulting_balance: cost.map(|c| format_sats(balance.saturating_sub(c))),
+    sufficient,
+    expires,
+  })
+}
+
+/// Everything this wallet burned, with totals per kind
+#[tauri::command]
+pub fn get_burn_report() -> Result<BurnReport, String> {
+  ensure_config()?;
+  let burns = wallet_burns()?;
+  let mut totals: HashMap<String, (u32, u64)> = HashMap::new();
+  let mut fees: HashMap<String, u64> = HashMap::new();
+  let mut total = 0u64;
+  for (record, amount, fee) in &burns {
+    let entry = totals.entry(record.kind.clone()).or_default();
+    entry.0 += 1;
+    entry.1 += amount;
+    total += amount;
+    // The fee is repeated on every output entry of a transaction
+    fees.insert(record.txid.clone(), *fee);
+  }
+  let mut by_kind: Vec<BurnKindTotal> = totals
+    .into_iter()
+    .map(|(kind, (count, sum))| BurnKindTotal { kind, count, total: format_sats(sum) })
+    .collect();
+  by_kind.sort_by(|a, b| a.kind.cmp(&b.kind));
+
+  Ok(BurnReport {
+    total: format_sats(total),
+    fees: format_sats(fees.values().sum()),
+    transactions: fees.len() as u32,
+    by_kind,
+    records: burns.into_iter().rev().map(|(record, _, _)| record).collect(),
+  })
+}
@@ -0,0 +1,234 @@
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+use chrono::Local;
+
+// Import local modules
+use crate::modules::models::{FrozenCoin, LockedUtxo, RawTxInput, UtxoItem};
+use crate::modules::commands::run_cli;
+use crate::modules::files::{data_dir, ensure_config};
+
+// Node locks are in-memory only, so the commander keeps its own list of
+// frozen coins and puts them back after every node start.
+
+fn frozen_coins_path() -> Result<PathBuf, String> {
+  Ok(data_dir()?.join("frozen_coins.json"))
+}
+
+pub fn load_frozen_coins() -> Result<Vec<FrozenCoin>, String> {
+  let path = frozen_coins_path()?;
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+  let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+  let coins: Vec<FrozenCoin> = serde_json::from_str(&content).unwrap_or_default();
+  Ok(coins)
+}
+
+fn save_frozen_coins(coins: &[FrozenCoin]) -> Result<(), String> {
+  let path = frozen_coins_path()?;
+  let content = serde_json::to_string_pretty(coins).map_err(|e| e.to_string())?;
+  fs::write(&path, content).map_err(|e| e.to_string())?;
+  Ok(())
+}
+
+fn lockunspent(unlock: bool, utxos: &[RawTxInput]) -> Result<(), String> {
+  let json = serde_json::to_string(utxos).map_err(|e| e.to_string())?;
+  let flag = if unlock { "true" } else { "false" };
+  let result = run_cli(&[String::from("lockunspent"), flag.to_string(), json])?;
+  if result.trim() != "true" {
+    return Err(format!("lockunspent returned {}", result.trim()));
+  }
+  Ok(())
+}
+
+/// Outpoints currently locked in the running node
+pub fn node_locked_utxos() -> Result<Vec<RawTxInput>, String> {
+  let raw = run_cli(&[String::from("listlockunspent")])?;
+  serde_json::from_str(&raw).map_err(|e| e.to_string())
+}
+
+/// `listunspent` hides locked outputs, so build UTXO rows for them from `gettxout`
+pub fn locked_utxo_items() -> Result<Vec<UtxoItem>, String> {
+  let frozen = load_frozen_coins()?;
+  let mut items = Vec::new();
+  for utxo in node_locked_utxos()? {
+    let raw = run_cli(&[
+      String::from("gettxout"),
+      utxo.txid.clone(),
+      utxo.vout.to_string(),
+      String::from("true"),
+    ])?;
+    // Empty output means the coin has been spent since it was locked
+    let out: serde_json::Value = match serde_json::from_str(&raw) {
+      Ok(v) => v,
+      Err(_) => continue,
+    };
+    let address = out["scriptPubKey"]["addresses"]
+      .as_array()
+      .and_then(|a| a.first())
+      .and_then(|v| v.as_str())
+      .map(|s| s.to_string());
+    let is_frozen = frozen.iter().any(|c| c.txid == utxo.txid && c.vout == utxo.vout);
+    items.push(UtxoItem {
+      txid: utxo.txid,
+      vout: utxo.vout,
+      address,
+      amount: out["value"].as_f64().unwrap_or(0.0),
+      confirmations: out["confirmations"].as_u64().unwrap_or(0),
+      spendable: Some(false),
+      solvable: None,
+      desc: None,
+      safe: None,
+      script_pub_key: out["scriptPubKey"]["hex"].as_str().map(|s| s.to_string()),
+      locked: true,
+      frozen: is_frozen,
+    });
+  }
+  Ok(items)
+}
+
+/// `Some(false)` once the outpoint is spent; `None` when the node could not
+/// be asked. Mempool is included, so an unconfirmed frozen coin is kept.
+fn is_unspent(utxo: &RawTxInput) -> Option<bool> {
+  let raw = run_cli(&[
+    String::from("gettxout"),
+    utxo.txid.clone(),
+    utxo.vout.to_string(),
+    String::from("true"),
+  ])
+  .ok()?;
+  Some(serde_json::from_str::<serde_json::Value>(&raw).map(|v| v.is_object()).unwrap_or(false))
+}
+
+/// Re-lock every persisted frozen coin. Coins spent since they were frozen
+/// are dropped from `frozen_coins.json`; the rest are locked one by one if
+/// needed, so a single failing output does not stop the others.
+pub fn reapply_frozen_coins() -> Result<usize, String> {
+  let coins = load_frozen_coins()?;
+  if coins.is_empty() {
+    return Ok(0);
+  }
+  let (coins, spent): (Vec<FrozenCoin>, Vec<FrozenCoin>) = coins
+    .into_iter()
+    .partition(|c| is_unspent(&RawTxInput { txid: c.txid.clone(), vout: c.vout }) != Some(false));
+  if !spent.is_empty() {
+    log::info!("Dropping {} spent frozen coins", spent.len());
+    save_frozen_coins(&coins)?;
+  }
+  if coins.is_empty() {
+    return Ok(0);
+  }
+  let utxos: Vec<RawTxInput> = coins
+    .iter()
+    .map(|c| RawTxInput { txid: c.txid.clone(), vout: c.vout })
+    .collect();
+  if lockunspent(false, &utxos).is_ok() {
+    return Ok(utxos.len());
+  }
+  let mut applied = 0;
+  for utxo in utxos {
+    if lockunspent(false, std::slice::from_ref(&utxo)).is_ok() {
+      applied += 1;
+    }
+  }
+  Ok(applied)
+}
+
+/// Wait in the background for the freshly started node to answer RPC, then
+/// restore the frozen coin locks.
+pub fn reapply_frozen_coins_when_ready() {
+  thread::spawn(|| {
+    // Up to ~10 minutes; block index loading can be slow on big chains
+    for _ in 0..120 {
+      thread::sleep(Duration::from_secs(5));
+      if run_cli(&[String::from("getwalletinfo")]).is_ok() {
+        if let Err(e) = reapply_frozen_coins() {
+          log::warn!("Failed to re-apply frozen coins: {}", e);
+        }
+        return;
+      }
+    }
+    log::warn!("Node did not become ready, frozen coins were not re-applied");
+  });
+}
+
+#[tauri::command]
+pub fn lock_utxos(utxos: Vec<RawTxInput>, note: Option<String>) -> Result<(), String> {
+  ensure_config()?;
+  if utxos.is_empty() {
+    return Err("No UTXOs selected".to_string());
+  }
+  lockunspent(false, &utxos)?;
+
+  let mut coins = load_frozen_coins()?;
+  let date = Local::now().timestamp() as u64;
+  for utxo in utxos {
+    if !coins.iter().any(|c| c.txid == utxo.txid && c.vout == utxo.vout) {
+      coins.push(FrozenCoin {
+        txid: utxo.txid,
+        vout: utxo.vout,
+        note: note.clone().unwrap_or_default(),
+        date,
+      });
+    }
+  }
+  save_frozen_coins(&coins)
+}
+
+#[tauri::command]
+pub fn unlock_utxos(utxos: Vec<RawTxInput>) -> Result<(), String> {
+  ensure_config()?;
+  if utxos.is_empty() {
+    return Err("No UTXOs selected".to_string());
+  }
+  // Only unlock what the node actually has locked, otherwise lockunspent fails
+  let locked = node_locked_utxos().unwrap_or_default();
+  let to_unlock: Vec<RawTxInput> = utxos.iter().filter(|u| locked.contains(u)).cloned().collect();
+  if !to_unlock.is_empty() {
+    lockunspent(true, &to_unlock)?;
+  }
+
+  let mut coins = load_frozen_coins()?;
+  coins.retain(|c| !utxos.iter().any(|u| u.txid == c.txid && u.vout == c.vout));
+  save_frozen_coins(&coins)
+}
+
+#[tauri::command]
+pub fn list_locked_utxos() -> Result<Vec<LockedUtxo>, String> {
+  ensure_config()?;
+  let locked = node_locked_utxos()?;
+  let frozen = load_frozen_coins()?;
+
+  let mut items: Vec<LockedUtxo> = frozen
+    .iter()
+    .map(|c| LockedUtxo {
+      txid: c.txid.clone(),
+      vout: c.vout,
+      note: c.note.clone(),
+      frozen: true,
+      locked: locked.iter().any(|u| u.txid == c.txid && u.vout == c.vout),
+    })
+    .collect();
+
+  // Locks made outside the commander (e.g. from the console)
+  for utxo in locked {
+    if !frozen.iter().any(|c| c.txid == utxo.txid && c.vout == utxo.vout) {
+      items.push(LockedUtxo {
+        txid: utxo.txid,
+        vout: utxo.vout,
+        note: String::new(),
+        frozen: false,
+        locked: true,
+      });
+    }
+  }
+  Ok(items)
+}
+
+#[tauri::command]
+pub fn apply_frozen_coins() -> Result<usize, String> {
+  ensure_config()?;
+  reapply_frozen_coins()
+}
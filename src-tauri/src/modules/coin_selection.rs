@@ -0,0 +1,388 @@
+use std::collections::HashMap;
+use rand::Rng;
+
+// Import local modules
+use crate::modules::models::{CoinSelection, UtxoItem};
+use crate::modules::utils::{to_sats, parse_amount_sats};
+use crate::modules::commands::{run_cli, list_utxos};
+use crate::modules::files::ensure_config;
+use crate::modules::fees::{
+  resolve_fee_rate, fee_for_size, plan_fee, estimate_tx_size,
+  P2PKH_INPUT_SIZE, P2PKH_OUTPUT_SIZE, DUST_THRESHOLD_SATS, MAX_STANDARD_TX_SIZE,
+};
+
+const BNB_MAX_TRIES: usize = 100_000;
+const KNAPSACK_ITERATIONS: usize = 1000;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Strategy {
+  BranchAndBound,
+  LargestFirst,
+  SmallestFirst,
+  Privacy,
+  MinimalChange,
+}
+
+impl Strategy {
+  pub fn parse(name: &str) -> Result<Strategy, String> {
+    match name {
+      "bnb" | "branch_and_bound" => Ok(Strategy::BranchAndBound),
+      "largest_first" => Ok(Strategy::LargestFirst),
+      "smallest_first" | "consolidate" => Ok(Strategy::SmallestFirst),
+      "privacy" => Ok(Strategy::Privacy),
+      "minimal_change" => Ok(Strategy::MinimalChange),
+      _ => Err(format!("Unknown coin selection strategy: {}", name)),
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Strategy::BranchAndBound => "bnb",
+      Strategy::LargestFirst => "largest_first",
+      Strategy::SmallestFirst => "smallest_first",
+      Strategy::Privacy => "privacy",
+      Strategy::MinimalChange => "minimal_change",
+    }
+  }
+}
+
+/// A spendable coin with the fee for spending it already taken off
+#[derive(Clone)]
+pub struct Candidate {
+  /// position in the `list_utxos` result
+  pub utxo_index: usize,
+  pub value: u64,
+  pub effective: u64,
+  pub group: usize,
+}
+
+/// Amounts the selection has to cover, all in sats
+pub struct Targets {
+  /// outputs + base fee, no change output
+  pub exact: u64,
+  /// outputs + base fee including a change output
+  pub with_change: u64,
+  /// extra an exact match may overshoot before a change output is cheaper
+  pub cost_of_change: u64,
+}
+
+pub fn build_targets(output_total: u64, output_count: usize, fee_rate: f64) -> Targets {
+  let base_no_change = estimate_tx_size(0, output_count);
+  let change_fee = fee_for_size(P2PKH_OUTPUT_SIZE, fee_rate);
+  Targets {
+    exact: output_total + fee_for_size(base_no_change, fee_rate),
+    with_change: output_total + fee_for_size(base_no_change + P2PKH_OUTPUT_SIZE, fee_rate),
+    cost_of_change: change_fee + DUST_THRESHOLD_SATS,
+  }
+}
+
+fn max_inputs() -> usize {
+  (MAX_STANDARD_TX_SIZE / P2PKH_INPUT_SIZE) as usize - 10
+}
+
+fn sum_effective(cands: &[Candidate], picked: &[usize]) -> u64 {
+  picked.iter().map(|i| cands[*i].effective).sum()
+}
+
+/// Walk candidates in the given order until the target is reached
+fn accumulate(cands: &[Candidate], order: &[usize], target: u64) -> Option<Vec<usize>> {
+  let mut picked = Vec::new();
+  let mut total = 0u64;
+  for i in order {
+    if picked.len() >= max_inputs() {
+      return None;
+    }
+    picked.push(*i);
+    total += cands[*i].effective;
+    if total >= target {
+      return Some(picked);
+    }
+  }
+  None
+}
+
+pub fn largest_first(cands: &[Candidate], target: u64) -> Option<Vec<usize>> {
+  let mut order: Vec<usize> = (0..cands.len()).collect();
+  order.sort_by(|a, b| cands[*b].effective.cmp(&cands[*a].effective));
+  accumulate(cands, &order, target)
+}
+
+pub fn smallest_first(cands: &[Candidate], target: u64) -> Option<Vec<usize>> {
+  let mut order: Vec<usize> = (0..cands.len()).collect();
+  order.sort_by(|a, b| cands[*a].effective.cmp(&cands[*b].effective));
+  accumulate(cands, &order, target)
+}
+
+/// Depth-first search for a subset landing in `[target, target + cost_of_change]`,
+/// so no change output is needed. Gives up after `BNB_MAX_TRIES` steps.
+pub fn branch_and_bound(cands: &[Candidate], target: u64, cost_of_change: u64) -> Option<Vec<usize>> {
+  let mut order: Vec<usize> = (0..cands.len()).collect();
+  order.sort_by(|a, b| cands[*b].effective.cmp(&cands[*a].effective));
+  let values: Vec<u64> = order.iter().map(|i| cands[*i].effective).collect();
+  let total: u64 = values.iter().sum();
+  if total < target {
+    return None;
+  }
+
+  struct Search<'a> {
+    values: &'a [u64],
+    target: u64,
+    upper: u64,
+    tries: usize,
+    current: Vec<usize>,
+    best: Option<(u64, Vec<usize>)>,
+  }
+
+  fn step(s: &mut Search, idx: usize, sum: u64, remaining: u64) {
+    if s.tries == 0 {
+      return;
+    }
+    s.tries -= 1;
+    if sum > s.upper {
+      return;
+    }
+    if sum >= s.target {
+      let waste = sum - s.target;
+      if s.best.as_ref().map(|(w, _)| waste < *w).unwrap_or(true) {
+        s.best = Some((waste, s.current.clone()));
+      }
+      return;
+    }
+    if idx >= s.values.len() || sum + remaining < s.target || s.current.len() >= max_inputs() {
+      return;
+    }
+    let v = s.values[idx];
+    s.current.push(idx);
+    step(s, idx + 1, sum + v, remaining - v);
+    s.current.pop();
+    // Skipping a value equal to the one just tried explores the same sums again
+    let mut next = idx + 1;
+    let mut rest = remaining - v;
+    while next < s.values.len() && s.values[next] == v {
+      rest -= s.values[next];
+      next += 1;
+    }
+    step(s, next, sum, rest);
+  }
+
+  let mut search = Search {
+    values: &values,
+    target,
+    upper: target + cost_of_change,
+    tries: BNB_MAX_TRIES,
+    current: Vec::new(),
+    best: None,
+  };
+  step(&mut search, 0, 0, total);
+  search
+    .best
+    .map(|(_, picked)| picked.into_iter().map(|p| order[p]).collect())
+}
+
+/// Randomised subset-sum approximation (the classic knapsack pass): looks
+/// for the smallest total that still reaches the target.
+pub fn minimal_change(cands: &[Candidate], target: u64) -> Option<Vec<usize>> {
+  // A single coin that covers the target is often the tightest fit
+  let single = (0..cands.len())
+    .filter(|i| cands[*i].effective >= target)
+    .min_by_key(|i| cands[*i].effective)
+    .map(|i| vec![i]);
+
+  let mut order: Vec<usize> = (0..cands.len()).filter(|i| cands[*i].effective < target).collect();
+  order.sort_by(|a, b| cands[*b].effective.cmp(&cands[*a].effective));
+  let values: Vec<u64> = order.iter().map(|i| cands[*i].effective).collect();
+
+  let mut best: Option<(u64, Vec<bool>)> = None;
+  if values.iter().sum::<u64>() >= target {
+    let mut rng = rand::thread_rng();
+    for _ in 0..KNAPSACK_ITERATIONS {
+      let mut included = vec![false; values.len()];
+      let mut total = 0u64;
+      let mut reached = false;
+      for pass in 0..2 {
+        if reached {
+          break;
+        }
+        for i in 0..values.len() {
+          let take = if pass == 0 { rng.gen_bool(0.5) } else { !included[i] };
+          if !take {
+            continue;
+          }
+          total += values[i];
+          included[i] = true;
+          if total >= target {
+            reached = true;
+            if best.as_ref().map(|(b, _)| total < *b).unwrap_or(true) {
+              best = Some((total, included.clone()));
+            }
+            total -= values[i];
+            included[i] = false;
+          }
+        }
+      }
+    }
+  }
+
+  let subset = best.and_then(|(total, included)| {
+    let picked: Vec<usize> = included
+      .iter()
+      .enumerate()
+      .filter(|(_, inc)| **inc)
+      .map(|(i, _)| order[i])
+      .collect();
+    if picked.len() > max_inputs() {
+      None
+    } else {
+      Some((total, picked))
+    }
+  });
+
+  match (single, subset) {
+    (Some(s), Some((total, picked))) => {
+      if sum_effective(cands, &s) <= total { Some(s) } else { Some(picked) }
+    }
+    (Some(s), None) => Some(s),
+    (None, Some((_, picked))) => Some(picked),
+    (None, None) => None,
+  }
+}
+
+/// Fund the target from a single address grouping, so unrelated addresses
+/// are never linked on-chain. Picks the group that leaves the least change.
+pub fn privacy(cands: &[Candidate], target: u64) -> Option<Vec<usize>> {
+  let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+  for (pos, c) in cands.iter().enumerate() {
+    groups.entry(c.group).or_default().push(pos);
+  }
+  groups
+    .values()
+    .filter_map(|positions| {
+      let members: Vec<Candidate> = positions.iter().map(|p| cands[*p].clone()).collect();
+      let picked = largest_first(&members, target)?;
+      let chosen: Vec<usize> = picked.iter().map(|p| positions[*p]).collect();
+      Some((sum_effective(cands, &chosen), chosen))
+    })
+    .min_by_key(|(total, _)| *total)
+    .map(|(_, chosen)| chosen)
+}
+
+/// Map every wallet address to its `listaddressgroupings` group index
+fn address_groups() -> Result<HashMap<String, usize>, String> {
+  let raw = run_cli(&[String::from("listaddressgroupings")])?;
+  let value: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  let mut map = HashMap::new();
+  if let Some(groups) = value.as_array() {
+    for (idx, group) in groups.iter().enumerate() {
+      for entry in group.as_array().unwrap_or(&Vec::new()) {
+        if let Some(addr) = entry.get(0).and_then(|v| v.as_str()) {
+          map.insert(addr.to_string(), idx);
+        }
+      }
+    }
+  }
+  Ok(map)
+}
+
+/// Turn spendable UTXOs into candidates; coins worth less than their own
+/// input fee are dropped since spending them loses money.
+pub fn build_candidates(utxos: &[UtxoItem], fee_rate: f64, groups: &HashMap<String, usize>) -> Vec<Candidate> {
+  let input_fee = fee_for_size(P2PKH_INPUT_SIZE, fee_rate);
+  let mut next_group = groups.values().max().map(|m| m + 1).unwrap_or(0);
+  let mut cands = Vec::new();
+  for (utxo_index, u) in utxos.iter().enumerate() {
+    if u.locked || u.spendable == Some(false) || u.safe == Some(false) {
+      continue;
+    }
+    let value = to_sats(u.amount);
+    if value <= input_fee {
+      continue;
+    }
+    let group = match u.address.as_ref().and_then(|a| groups.get(a)) {
+      Some(g) => *g,
+      None => {
+        next_group += 1;
+        next_group
+      }
+    };
+    cands.push(Candidate { utxo_index, value, effective: value - input_fee, group });
+  }
+  cands
+}
+
+/// Run one strategy over the candidates. Returns indexes into `cands`, the
+/// strategy actually used, and a note when it had to fall back.
+pub fn run_strategy(
+  strategy: Strategy,
+  cands: &[Candidate],
+  targets: &Targets,
+) -> Result<(Vec<usize>, Strategy, Option<String>), String> {
+  let picked = match strategy {
+    Strategy::BranchAndBound => {
+      if let Some(p) = branch_and_bound(cands, targets.exact, targets.cost_of_change) {
+        return Ok((p, strategy, None));
+      }
+      return largest_first(cands, targets.with_change)
+        .map(|p| (p, Strategy::LargestFirst, Some("No exact match found; fell back to largest-first".to_string())))
+        .ok_or_else(|| "Insufficient funds".to_string());
+    }
+    Strategy::LargestFirst => largest_first(cands, targets.with_change),
+    Strategy::SmallestFirst => smallest_first(cands, targets.with_change),
+    Strategy::Privacy => {
+      return privacy(cands, targets.with_change)
+        .map(|p| (p, strategy, None))
+        .ok_or_else(|| "No single address group holds enough to fund this without mixing addresses".to_string());
+    }
+    Strategy::MinimalChange => {
+      if let Some(p) = branch_and_bound(cands, targets.exact, targets.cost_of_change) {
+        return Ok((p, strategy, None));
+      }
+      minimal_change(cands, targets.with_change)
+    }
+  };
+  picked
+    .map(|p| (p, strategy, None))
+    .ok_or_else(|| "Insufficient funds".to_string())
+}
+
+#[tauri::command]
+pub fn select_coins(
+  outputs: HashMap<String, String>,
+  strategy: String,
+  fee_rate: Option<f64>,
+  conf_target: Option<u32>,
+) -> Result<CoinSelection, String> {
+  ensure_config()?;
+  if outputs.is_empty() {
+    return Err("At least one output is required".to_string());
+  }
+  let strategy = Strategy::parse(strategy.trim())?;
+  let estimate = resolve_fee_rate(fee_rate, conf_target)?;
+
+  let mut output_total = 0u64;
+  for amount in outputs.values() {
+    output_total += parse_amount_sats(amount)?;
+  }
+
+  let utxos = list_utxos()?;
+  let groups = if strategy == Strategy::Privacy { address_groups()? } else { HashMap::new() };
+  let cands = build_candidates(&utxos, estimate.fee_rate, &groups);
+  let targets = build_targets(output_total, outputs.len(), estimate.fee_rate);
+
+  let (picked, used, note) = run_strategy(strategy, &cands, &targets)?;
+  let inputs: Vec<UtxoItem> = picked.iter().map(|i| utxos[cands[*i].utxo_index].clone()).collect();
+  let input_total: u64 = picked.iter().map(|i| cands[*i].value).sum();
+
+  let change_address = if sum_effective(&cands, &picked) >= targets.with_change + DUST_THRESHOLD_SATS {
+    Some(run_cli(&[String::from("getrawchangeaddress")])?)
+  } else {
+    None
+  };
+  let plan = plan_fee(input_total, inputs.len(), &outputs, estimate.fee_rate, change_address)?;
+
+  Ok(CoinSelection {
+    strategy: used.name().to_string(),
+    inputs,
+    plan,
+    note,
+  })
+}
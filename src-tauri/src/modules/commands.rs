@@ -10,6 +10,19 @@ use chrono::{Local, TimeZone, DateTime};
 use crate::modules::models::*;
 use crate::modules::utils::{resolve_bin, split_args, parse_balances, version_is_old};
 use crate::modules::files::{data_dir, ensure_config, parse_config, config_path};
+use crate::modules::fees::replan_confirmed;
+use crate::modules::coin_control::locked_utxo_items;
+use crate::modules::watch_only::watch_only_summary;
+use crate::modules::seed_backup::seed_backup_needed;
+use crate::modules::rescan::rescan_after_import;
+use crate::modules::safety_backup::{discard_snapshot, snapshot_wallet};
+use crate::modules::wallet_session::{start_session, lock_for, session_info};
+use crate::modules::wallets::{rpc_wallet, wallet_balances};
+use crate::modules::restricted::reissue_restricted_asset;
+use crate::modules::qualifiers::tags_by_address;
+use crate::modules::transfers::{build_options, send_asset};
+use crate::modules::naming::{check_asset_name, expect_asset_kind, onchain_ipfs_hash, AssetKind};
+use crate::modules::burns::take_quote;
 
 // --- SHELL STATE ---
 #[derive(Default)]
@@ -35,6 +48,11 @@ fn shell_state() -> &'static Mutex<ShellState> {
 
 // --- CLI RUNNER ---
 pub fn run_cli(args: &[String]) -> Result<String, String> {
+  run_cli_for_wallet(rpc_wallet().as_deref(), args)
+}
+
+/// Like `run_cli`, but wallet RPCs go to `wallet` instead of the active one
+pub fn run_cli_for_wallet(wallet: Option<&str>, args: &[String]) -> Result<String, String> {
   let cfg = ensure_config()?;
   let dir = data_dir()?;
   let cli = resolve_bin("hemp0x-cli");
@@ -64,6 +82,9 @@ pub fn run_cli(args: &[String]) -> Result<String, String> {
   } else if is_testnet {
     cmd.arg("-testnet");
   }
+  if let Some(name) = wallet {
+    cmd.arg(format!("-rpcwallet={}", name));
+  }
   
   let output = cmd
     .arg(format!("-conf={}", cfg.to_string_lossy()))
@@ -177,8 +198,13 @@ pub fn dashboard_data() -> Result<DashboardData, String> {
             pending: "--".to_string(),
             staked: "--".to_string(),
             status: "--".to_string(),
+            unlock_remaining: None,
+            staking_only: false,
         },
         tx: Vec::new(),
+        watch_only: None,
+        seed_backup_needed: false,
+        wallets: Vec::new(),
      });
   }
 
@@ -226,11 +252,14 @@ pub fn dashboard_data() -> Result<DashboardData, String> {
     synced,
   };
 
+  let session = if unlocked_until.unwrap_or(0) > 0 { session_info() } else { None };
   let wallet = WalletInfo {
     balance: format!("{:.3}", balance_val),
     pending: format!("{:.3}", pending_val),
     staked: format!("{:.3}", staked_val),
     status: status.to_string(),
+    unlock_remaining: session.map(|(remaining, _)| remaining),
+    staking_only: session.map(|(_, staking)| staking).unwrap_or(false),
   };
 
   let tx_raw = run_cli(&[
@@ -277,6 +306,9 @@ pub fn dashboard_data() -> Result<DashboardData, String> {
     node,
     wallet,
     tx: txs,
+    watch_only: watch_only_summary(),
+    seed_backup_needed: seed_backup_needed(),
+    wallets: wallet_balances(),
   })
 }
 
@@ -315,6 +347,7 @@ pub fn get_receive_addresses(show_change: bool) -> Result<Vec<AddressItem>, Stri
         label,
         address: addr.clone(),
         balance: format!("{:.8}", bal),
+        tags: Vec::new(),
       });
       seen.insert(addr, true);
     }
@@ -327,11 +360,18 @@ pub fn get_receive_addresses(show_change: bool) -> Result<Vec<AddressItem>, Stri
           label: "(Change)".to_string(),
           address: addr,
           balance: format!("{:.8}", bal),
+          tags: Vec::new(),
         });
       }
     }
   }
 
+  let addresses: Vec<String> = items.iter().map(|i| i.address.clone()).collect();
+  let mut tags = tags_by_address(&addresses);
+  for item in items.iter_mut() {
+    item.tags = tags.remove(&item.address).unwrap_or_default();
+  }
+
   Ok(items)
 }
 
@@ -413,15 +453,42 @@ pub fn list_assets() -> Result<Vec<AssetItem>, String> {
   Ok(items)
 }
 
+/// Transfer an asset. The optional memo (IPFS hash or txid), expiry, change
+/// addresses and source addresses map onto the node's `transfer` family.
 #[tauri::command]
-pub fn transfer_asset(asset: String, amount: String, to: String) -> Result<String, String> {
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_asset(
+  asset: String,
+  amount: String,
+  to: String,
+  memo: Option<String>,
+  expire_time: Option<u64>,
+  change_address: Option<String>,
+  asset_change_address: Option<String>,
+  from_addresses: Option<Vec<String>>,
+) -> Result<String, String> {
   ensure_config()?;
-  run_cli(&[String::from("transfer"), asset, amount, to])
+  let options = build_options(memo, expire_time, change_address, asset_change_address, from_addresses)?;
+  send_asset(asset.trim(), amount.trim(), to.trim(), &options)
 }
 
 #[tauri::command]
-pub fn issue_asset(name: String, qty: String, units: u8, reissuable: bool, ipfs: String) -> Result<String, String> {
+pub fn issue_asset(
+  name: String,
+  qty: String,
+  units: u8,
+  reissuable: bool,
+  ipfs: String,
+  quote_id: String,
+) -> Result<String, String> {
   ensure_config()?;
+  let name = name.trim().to_string();
+  expect_asset_kind(
+    &name,
+    &[AssetKind::Root, AssetKind::Sub, AssetKind::Unique, AssetKind::Channel],
+    "issued here",
+  )?;
+  let ipfs = onchain_ipfs_hash(&ipfs)?;
   let qty_val: f64 = qty
     .trim()
     .parse()
@@ -430,6 +497,7 @@ pub fn issue_asset(name: String, qty: String, units: u8, reissuable: bool, ipfs:
     return Err("Units must be between 0 and 8".to_string());
   }
   let flag = if reissuable { "true" } else { "false" };
+  take_quote(&quote_id, "issue", &name, 1)?;
   
   if !ipfs.is_empty() {
     run_cli(&[
@@ -506,8 +574,30 @@ pub fn reissue_asset(
   change_verifier: bool,
   new_verifier: String,
   new_ipfs: String,
+  quote_id: String,
 ) -> Result<String, String> {
   ensure_config()?;
+  // Only restricted assets carry a verifier; they have their own reissue call
+  if name.starts_with('$') {
+    return reissue_restricted_asset(
+      name,
+      qty,
+      to_address,
+      change_verifier,
+      new_verifier,
+      None,
+      None,
+      true,
+      new_ipfs,
+      quote_id,
+    );
+  }
+  if change_verifier {
+    return Err("Only restricted ($) assets have a verifier string".to_string());
+  }
+  let name = name.trim().to_string();
+  expect_asset_kind(&name, &[AssetKind::Root, AssetKind::Sub], "reissued")?;
+  let new_ipfs = onchain_ipfs_hash(&new_ipfs)?;
   let qty_val: f64 = qty
     .trim()
     .parse()
@@ -520,18 +610,14 @@ pub fn reissue_asset(
     to_address,
   ];
   
-  if change_verifier {
-    args.push(String::from("true"));
-    args.push(new_verifier);
-  }
-  
   if !new_ipfs.is_empty() {
-    if args.len() < 6 {
-      args.push(String::from("false"));
-      args.push(String::new());
-    }
+    // change_address, reissuable, new_units (-1 keeps them) come before new_ipfs
+    args.push(String::new());
+    args.push(String::from("true"));
+    args.push(String::from("-1"));
     args.push(new_ipfs);
   }
+  take_quote(&quote_id, "reissue", &args[1], 1)?;
   
   run_cli(&args)
 }
@@ -541,20 +627,35 @@ pub fn issue_unique_asset(
   root_name: String,
   tags: Vec<String>,
   ipfs_hashes: Vec<String>,
+  quote_id: String,
 ) -> Result<String, String> {
   ensure_config()?;
   
   if tags.is_empty() {
     return Err("At least one tag is required".to_string());
   }
+  let root_name = root_name.trim().to_string();
+  expect_asset_kind(&root_name, &[AssetKind::Root, AssetKind::Sub], "the parent of unique assets")?;
+  for tag in &tags {
+    check_asset_name(&format!("{}#{}", root_name, tag))?;
+  }
   
   let tags_json = serde_json::to_string(&tags).map_err(|e| e.to_string())?;
   
   let ipfs_json = if !ipfs_hashes.is_empty() && ipfs_hashes.iter().any(|h| !h.is_empty()) {
-    serde_json::to_string(&ipfs_hashes).map_err(|e| e.to_string())?
+    if ipfs_hashes.len() != tags.len() {
+      return Err(format!("{} tags but {} IPFS hashes; give one hash per tag", tags.len(), ipfs_hashes.len()));
+    }
+    let hashes = ipfs_hashes
+      .iter()
+      .zip(&tags)
+      .map(|(h, tag)| onchain_ipfs_hash(h).map_err(|e| format!("{}#{}: {}", root_name, tag, e)))
+      .collect::<Result<Vec<_>, _>>()?;
+    serde_json::to_string(&hashes).map_err(|e| e.to_string())?
   } else {
     String::from("[]")
   };
+  take_quote(&quote_id, "issue_unique", &root_name, tags.len() as u32)?;
   
   run_cli(&[
     String::from("issueunique"),
@@ -640,21 +741,72 @@ pub fn dump_priv_key(address: String) -> Result<String, String> {
 }
 
 #[tauri::command]
-pub fn import_priv_key(priv_key: String, label: String, rescan: bool) -> Result<String, String> {
+pub fn import_priv_key(
+  app_handle: tauri::AppHandle,
+  priv_key: String,
+  label: String,
+  rescan: bool,
+  rescan_from: Option<u64>,
+) -> Result<String, String> {
   ensure_config()?;
-  let rescan_flag = if rescan { "true" } else { "false" };
-  run_cli(&[
+  snapshot_wallet("import_key")?;
+  // Import without the blocking rescan, then scan from `rescan_from` in the background
+  let result = run_cli(&[
     String::from("importprivkey"),
     priv_key,
     label,
-    rescan_flag.to_string(),
-  ])
+    String::from("false"),
+  ])?;
+  rescan_after_import(app_handle, rescan, rescan_from);
+  Ok(result)
 }
 
+/// Import several keys behind a single safety copy. One snapshot per key
+/// would let retention prune the pre-import copy on a large batch.
+#[tauri::command]
+pub fn import_priv_keys(
+  app_handle: tauri::AppHandle,
+  keys: Vec<KeyImportItem>,
+  rescan: bool,
+  rescan_from: Option<u64>,
+) -> Result<KeyImportResult, String> {
+  ensure_config()?;
+  if keys.is_empty() {
+    return Err("No keys selected".to_string());
+  }
+  snapshot_wallet("import_keys")?;
+  let mut imported = 0;
+  let mut failed = 0;
+  for key in keys {
+    let result = run_cli(&[
+      String::from("importprivkey"),
+      key.priv_key,
+      key.label,
+      String::from("false"),
+    ]);
+    match result {
+      Ok(_) => imported += 1,
+      Err(e) => {
+        log::warn!("Key import failed: {}", e);
+        failed += 1;
+      }
+    }
+  }
+  if imported > 0 {
+    rescan_after_import(app_handle, rescan, rescan_from);
+  }
+  Ok(KeyImportResult { imported, failed })
+}
+
+/// The safety copy taken here holds the unencrypted keys, so it is wiped
+/// once encryption succeeds and only kept if `encryptwallet` failed.
 #[tauri::command]
 pub fn wallet_encrypt(password: String) -> Result<String, String> {
   ensure_config()?;
-  run_cli(&[String::from("encryptwallet"), password])
+  let snapshot = snapshot_wallet("encrypt")?;
+  let result = run_cli(&[String::from("encryptwallet"), password])?;
+  discard_snapshot(snapshot);
+  Ok(result)
 }
 
 #[tauri::command]
@@ -732,29 +884,96 @@ pub fn check_open_port(host: String, port: u16) -> Result<bool, String> {
 }
 
 #[tauri::command]
-pub fn wallet_unlock(password: String, duration: u64) -> Result<String, String> {
+pub fn wallet_unlock(
+  app_handle: tauri::AppHandle,
+  password: String,
+  duration: u64,
+  staking_only: Option<bool>,
+) -> Result<String, String> {
   ensure_config()?;
-  run_cli(&[
-    String::from("walletpassphrase"),
-    password,
-    duration.to_string(),
-  ])
+  start_session(app_handle, password, duration, staking_only.unwrap_or(false))
 }
 
 #[tauri::command]
-pub fn wallet_lock() -> Result<String, String> {
+pub fn wallet_lock(app_handle: tauri::AppHandle) -> Result<String, String> {
   ensure_config()?;
-  run_cli(&[String::from("walletlock")])
+  lock_for(&app_handle, "manual")?;
+  Ok(String::new())
+}
+
+/// `None` for an unencrypted wallet, otherwise whether it is currently unlocked
+pub fn wallet_unlocked() -> Result<Option<bool>, String> {
+  let raw = run_cli(&[String::from("getwalletinfo")])?;
+  let info: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  Ok(info["unlocked_until"].as_i64().map(|until| until > 0))
+}
+
+/// Run `f` with the wallet unlocked. A locked wallet is unlocked briefly
+/// with `passphrase` and locked again afterwards; a wallet the user had
+/// already unlocked is left as it was. A staking-only unlock cannot spend
+/// or dump keys, so it is swapped for a full unlock and put back after.
+pub fn with_unlocked_wallet<T>(
+  passphrase: Option<&str>,
+  f: impl FnOnce() -> Result<T, String>,
+) -> Result<T, String> {
+  with_unlocked_wallet_for(passphrase, 60, |_| f())
+}
+
+/// Like `with_unlocked_wallet`, with the unlock lasting `secs`. `f` gets the
+/// passphrase when this call did the unlock, so a long running call can
+/// renew it; `None` means the wallet was already unlocked by the user.
+pub fn with_unlocked_wallet_for<T>(
+  passphrase: Option<&str>,
+  secs: u64,
+  f: impl FnOnce(Option<&str>) -> Result<T, String>,
+) -> Result<T, String> {
+  let staking_only = session_info().map(|(_, staking_only)| staking_only).unwrap_or(false);
+  let unlocked = wallet_unlocked()?;
+  if unlocked != Some(false) && !staking_only {
+    return f(None);
+  }
+  let pass = passphrase
+    .filter(|p| !p.is_empty())
+    .ok_or(if staking_only {
+      "Wallet is unlocked for staking only. Enter the wallet password to continue."
+    } else {
+      "Wallet is locked. Enter the wallet password to continue."
+    })?;
+  if unlocked == Some(true) {
+    // Some nodes refuse walletpassphrase on an unlocked wallet
+    let _ = run_cli(&[String::from("walletlock")]);
+  }
+  run_cli(&[String::from("walletpassphrase"), pass.to_string(), secs.to_string()])?;
+  let result = f(Some(pass));
+  let _ = run_cli(&[String::from("walletlock")]);
+  if staking_only {
+    if let Some((remaining, _)) = session_info().filter(|(remaining, _)| *remaining > 0) {
+      let restored = run_cli(&[
+        String::from("walletpassphrase"),
+        pass.to_string(),
+        remaining.to_string(),
+        String::from("true"),
+      ]);
+      if let Err(e) = restored {
+        log::warn!("Could not restore the staking-only unlock: {}", e);
+      }
+    }
+  }
+  result
 }
 
 #[tauri::command]
 pub fn change_wallet_password(old_pass: String, new_pass: String) -> Result<String, String> {
   ensure_config()?;
-  run_cli(&[
+  // The copy still opens with the old password; wiped once the change succeeds
+  let snapshot = snapshot_wallet("password_change")?;
+  let result = run_cli(&[
     String::from("walletpassphrasechange"),
     old_pass,
     new_pass,
-  ])
+  ])?;
+  discard_snapshot(snapshot);
+  Ok(result)
 }
 
 #[tauri::command]
@@ -912,7 +1131,10 @@ pub fn list_utxos() -> Result<Vec<UtxoItem>, String> {
     String::from("[]"),
     String::from("true"),
   ])?;
-  let utxos: Vec<UtxoItem> = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  let mut utxos: Vec<UtxoItem> = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+
+  // listunspent skips locked coins; list them too so they can be unlocked
+  utxos.extend(locked_utxo_items().unwrap_or_default());
   Ok(utxos)
 }
 
@@ -920,11 +1142,20 @@ pub fn list_utxos() -> Result<Vec<UtxoItem>, String> {
 pub fn broadcast_advanced_transaction(
   inputs: Vec<RawTxInput>,
   outputs: HashMap<String, String>,
+  plan: TxFeePlan,
 ) -> Result<String, String> {
   ensure_config()?;
 
-  let inputs_json = serde_json::to_string(&inputs).map_err(|e| e.to_string())?;
-  let outputs_json = serde_json::to_string(&outputs).map_err(|e| e.to_string())?;
+  // Sends exactly the previewed fee and change, checked again here so a bad
+  // selection can't burn the remainder
+  let plan = replan_confirmed(&inputs, &outputs, &plan)?;
+  create_sign_send(&inputs, &plan.outputs)
+}
+
+/// createrawtransaction -> signrawtransaction -> sendrawtransaction
+pub fn create_sign_send(inputs: &[RawTxInput], outputs: &HashMap<String, String>) -> Result<String, String> {
+  let inputs_json = serde_json::to_string(inputs).map_err(|e| e.to_string())?;
+  let outputs_json = serde_json::to_string(outputs).map_err(|e| e.to_string())?;
   
   let raw_hex = run_cli(&[
     String::from("createrawtransaction"),
@@ -969,8 +1200,10 @@ pub fn backup_wallet_to(path: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub fn lock_asset_supply(name: String, current_units: u8) -> Result<String, String> {
+pub fn lock_asset_supply(name: String, current_units: u8, quote_id: String) -> Result<String, String> {
   ensure_config()?;
+  // Locking is a reissue, so it pays the reissue burn
+  take_quote(&quote_id, "reissue", &name, 1)?;
   // To lock: reissue with amount 0, reissuable=false.
   // We need a destination address (can be same wallet).
   let to_addr = run_cli(&[String::from("getnewaddress")])?;
@@ -989,8 +1222,19 @@ pub fn lock_asset_supply(name: String, current_units: u8) -> Result<String, Stri
 }
 
 #[tauri::command]
-pub fn update_asset_metadata(name: String, ipfs_hash: String, current_units: u8) -> Result<String, String> {
+pub fn update_asset_metadata(
+  name: String,
+  ipfs_hash: String,
+  current_units: u8,
+  quote_id: String,
+) -> Result<String, String> {
   ensure_config()?;
+  expect_asset_kind(&name, &[AssetKind::Root, AssetKind::Sub], "reissued")?;
+  if ipfs_hash.trim().is_empty() {
+    return Err("IPFS hash is required".to_string());
+  }
+  let ipfs_hash = onchain_ipfs_hash(&ipfs_hash)?;
+  take_quote(&quote_id, "reissue", &name, 1)?;
   // To update IPFS: reissue with amount 0, reissuable=true, same units, new IPFS.
   let to_addr = run_cli(&[String::from("getnewaddress")])?;
   let change_addr = to_addr.clone();
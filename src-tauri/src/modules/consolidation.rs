@@ -0,0 +1,228 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use tauri::Emitter;
+
+// Import local modules
+use crate::modules::models::{ConsolidationBatch, ConsolidationPlan, ConsolidationProgress, RawTxInput, UtxoItem};
+use crate::modules::utils::{to_sats, parse_amount_sats, format_sats};
+use crate::modules::commands::{run_cli, list_utxos, create_sign_send};
+use crate::modules::files::ensure_config;
+use crate::modules::fees::{
+  resolve_fee_rate, fee_for_size, estimate_tx_size, check_absurd_fee, is_my_address,
+  P2PKH_INPUT_SIZE, DUST_THRESHOLD_SATS, MAX_STANDARD_TX_SIZE,
+};
+
+pub const DEFAULT_MAX_INPUTS_PER_TX: usize = 500;
+
+static CONSOLIDATION_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Split the coins into one-output sweep transactions. Coins worth less
+/// than the fee to spend them are left alone, as are leftovers too small
+/// to form a batch of two.
+pub fn build_batches(
+  utxos: &[UtxoItem],
+  fee_rate: f64,
+  max_inputs: usize,
+) -> (Vec<ConsolidationBatch>, u64) {
+  let input_fee = fee_for_size(P2PKH_INPUT_SIZE, fee_rate);
+  let size_cap = ((MAX_STANDARD_TX_SIZE - estimate_tx_size(0, 1)) / P2PKH_INPUT_SIZE) as usize;
+  let per_tx = max_inputs.clamp(2, size_cap);
+
+  let mut economical: Vec<&UtxoItem> = Vec::new();
+  let mut skipped = 0u64;
+  for u in utxos {
+    if to_sats(u.amount) > input_fee {
+      economical.push(u);
+    } else {
+      skipped += 1;
+    }
+  }
+  economical.sort_by_key(|u| to_sats(u.amount));
+
+  let mut batches = Vec::new();
+  for chunk in economical.chunks(per_tx) {
+    let input_total: u64 = chunk.iter().map(|u| to_sats(u.amount)).sum();
+    let size = estimate_tx_size(chunk.len(), 1);
+    let fee = fee_for_size(size, fee_rate);
+    if chunk.len() < 2 || input_total < fee + DUST_THRESHOLD_SATS {
+      skipped += chunk.len() as u64;
+      continue;
+    }
+    batches.push(ConsolidationBatch {
+      inputs: chunk.iter().map(|u| RawTxInput { txid: u.txid.clone(), vout: u.vout }).collect(),
+      input_total: format_sats(input_total),
+      fee: format_sats(fee),
+      output: format_sats(input_total - fee),
+      size,
+    });
+  }
+  (batches, skipped)
+}
+
+#[tauri::command]
+pub fn plan_consolidation(
+  threshold: String,
+  address: Option<String>,
+  destination: Option<String>,
+  max_inputs_per_tx: Option<u32>,
+  fee_rate: Option<f64>,
+  conf_target: Option<u32>,
+  future_fee_rate: Option<f64>,
+) -> Result<ConsolidationPlan, String> {
+  ensure_config()?;
+  let threshold_sats = parse_amount_sats(&threshold)?;
+  if threshold_sats == 0 {
+    return Err("Threshold must be greater than zero".to_string());
+  }
+  let address = address.filter(|a| !a.trim().is_empty());
+  let estimate = resolve_fee_rate(fee_rate, conf_target)?;
+
+  let utxos: Vec<UtxoItem> = list_utxos()?
+    .into_iter()
+    .filter(|u| !u.locked && u.spendable != Some(false) && u.safe != Some(false))
+    .filter(|u| to_sats(u.amount) < threshold_sats)
+    .filter(|u| match &address {
+      Some(a) => u.address.as_deref() == Some(a.as_str()),
+      None => true,
+    })
+    .collect();
+  if utxos.len() < 2 {
+    return Err("Fewer than two UTXOs below the threshold; nothing to consolidate".to_string());
+  }
+
+  // Merging dust on one address keeps the coins on that address by default
+  let destination = match destination.filter(|d| !d.trim().is_empty()).or(address) {
+    Some(d) => d.trim().to_string(),
+    None => run_cli(&[String::from("getnewaddress")])?,
+  };
+  check_destination(&destination)?;
+
+  let max_inputs = max_inputs_per_tx.map(|m| m as usize).unwrap_or(DEFAULT_MAX_INPUTS_PER_TX);
+  let (batches, skipped) = build_batches(&utxos, estimate.fee_rate, max_inputs);
+  if batches.is_empty() {
+    return Err("No batch would be worth its fee at the current fee rate".to_string());
+  }
+
+  let total_fee: u64 = batches
+    .iter()
+    .map(|b| parse_amount_sats(&b.fee))
+    .collect::<Result<Vec<u64>, String>>()?
+    .iter()
+    .sum();
+  let merged: u64 = batches.iter().map(|b| b.inputs.len() as u64).sum();
+  // Each merged coin is one input less to pay for later; each batch leaves one output behind
+  let future_input_fee = fee_for_size(P2PKH_INPUT_SIZE, future_fee_rate.unwrap_or(estimate.fee_rate));
+  let future_savings = (merged - batches.len() as u64) * future_input_fee;
+
+  Ok(ConsolidationPlan {
+    destination,
+    fee_rate: estimate.fee_rate,
+    utxo_count: merged,
+    skipped_uneconomical: skipped,
+    total_fee: format_sats(total_fee),
+    future_savings: format_sats(future_savings),
+    worthwhile: future_savings > total_fee,
+    batches,
+  })
+}
+
+/// Check a batch sent back by the webview against the wallet: every input
+/// must be an unlocked, spendable and safe coin of this wallet, and the fee
+/// is what the inputs leave over the output, not what the plan says.
+fn verify_batch(
+  batch: &ConsolidationBatch,
+  utxos: &HashMap<(String, u64), UtxoItem>,
+  used: &mut HashSet<(String, u64)>,
+) -> Result<(), String> {
+  if batch.inputs.len() < 2 {
+    return Err("A batch needs at least two inputs".to_string());
+  }
+  let mut input_total = 0u64;
+  for input in &batch.inputs {
+    let key = (input.txid.clone(), input.vout);
+    let utxo = match utxos.get(&key) {
+      Some(u) if !u.locked && u.spendable != Some(false) && u.safe != Some(false) => u,
+      _ => return Err(format!("Input {}:{} is not a spendable coin of this wallet", input.txid, input.vout)),
+    };
+    if !used.insert(key) {
+      return Err(format!("Input {}:{} is used twice", input.txid, input.vout));
+    }
+    input_total += to_sats(utxo.amount);
+  }
+  let output = parse_amount_sats(&batch.output)?;
+  if output < DUST_THRESHOLD_SATS || output >= input_total {
+    return Err(format!(
+      "Batch output {} does not fit its inputs of {} HEMP",
+      batch.output,
+      format_sats(input_total)
+    ));
+  }
+  let fee = input_total - output;
+  if input_total != parse_amount_sats(&batch.input_total)? || fee != parse_amount_sats(&batch.fee)? {
+    return Err(format!(
+      "Batch totals do not match the wallet (inputs {} HEMP, fee {} HEMP); plan again",
+      format_sats(input_total),
+      format_sats(fee)
+    ));
+  }
+  check_absurd_fee(fee)
+}
+
+/// Consolidation only moves coins within the wallet
+fn check_destination(destination: &str) -> Result<(), String> {
+  if !is_my_address(destination)? {
+    return Err(format!("Destination {} does not belong to this wallet", destination));
+  }
+  Ok(())
+}
+
+/// Broadcast the plan's batches one after another on a background thread.
+/// Progress is reported through `consolidation-progress` events; the run
+/// stops at the first failed batch.
+#[tauri::command]
+pub fn start_consolidation(app_handle: tauri::AppHandle, plan: ConsolidationPlan) -> Result<(), String> {
+  ensure_config()?;
+  if plan.batches.is_empty() {
+    return Err("Plan has no batches".to_string());
+  }
+  let utxos: HashMap<(String, u64), UtxoItem> = list_utxos()?
+    .into_iter()
+    .map(|u| ((u.txid.clone(), u.vout), u))
+    .collect();
+  let mut used = HashSet::new();
+  for batch in &plan.batches {
+    verify_batch(batch, &utxos, &mut used)?;
+  }
+  // The plan comes back from the webview; never sweep to a foreign address
+  check_destination(&plan.destination)?;
+  if CONSOLIDATION_RUNNING.swap(true, Ordering::SeqCst) {
+    return Err("A consolidation is already running".to_string());
+  }
+
+  thread::spawn(move || {
+    let total = plan.batches.len();
+    for (idx, batch) in plan.batches.iter().enumerate() {
+      let mut outputs = HashMap::new();
+      outputs.insert(plan.destination.clone(), batch.output.clone());
+      let result = create_sign_send(&batch.inputs, &outputs);
+      let failed = result.is_err();
+      let (txid, error) = match result {
+        Ok(txid) => (Some(txid), None),
+        Err(e) => (None, Some(e)),
+      };
+      let _ = app_handle.emit("consolidation-progress", ConsolidationProgress {
+        batch: idx + 1,
+        total,
+        txid,
+        error,
+        done: failed || idx + 1 == total,
+      });
+      if failed {
+        break;
+      }
+    }
+    CONSOLIDATION_RUNNING.store(false, Ordering::SeqCst);
+  });
+  Ok(())
+}
@@ -0,0 +1,326 @@
+use std::collections::HashMap;
+
+// Import local modules
+use crate::modules::models::{FeeEstimate, RawTxInput, TxFeePlan, UtxoItem};
+use crate::modules::utils::{to_sats, parse_amount_sats, format_sats};
+use crate::modules::commands::run_cli;
+use crate::modules::files::{ensure_config, load_app_settings};
+
+// Legacy P2PKH sizes (bytes). The chain has no segwit, so vsize == size.
+pub const TX_OVERHEAD_SIZE: u64 = 10;
+pub const P2PKH_INPUT_SIZE: u64 = 148;
+pub const P2PKH_OUTPUT_SIZE: u64 = 34;
+
+pub const DEFAULT_CONF_TARGET: u32 = 6;
+// HEMP per kB, used when the node has no estimate yet (fresh node / regtest)
+pub const FALLBACK_FEE_RATE: f64 = 0.01;
+// Refuse to broadcast anything paying more than this unless settings override it
+pub const DEFAULT_MAX_FEE: f64 = 1.0;
+// Change below this is not worth an output and is left to the miner
+pub const DUST_THRESHOLD_SATS: u64 = 546;
+// Node relay policy rejects bigger transactions as non-standard
+pub const MAX_STANDARD_TX_SIZE: u64 = 100_000;
+
+pub fn estimate_tx_size(inputs: usize, outputs: usize) -> u64 {
+  TX_OVERHEAD_SIZE + inputs as u64 * P2PKH_INPUT_SIZE + outputs as u64 * P2PKH_OUTPUT_SIZE
+}
+
+/// Spending size of one P2SH m-of-n input: outpoint, sequence, the
+/// signatures (72 bytes + push each) and the redeem script itself
+pub fn multisig_input_size(required: u32, keys: u32) -> u64 {
+  49 + 73 * required as u64 + 34 * keys as u64
+}
+
+/// Fee in sats for `size` bytes at `fee_rate` HEMP/kB
+pub fn fee_for_size(size: u64, fee_rate: f64) -> u64 {
+  let rate_sats = to_sats(fee_rate);
+  (size * rate_sats).div_ceil(1000)
+}
+
+fn relay_fee_rate() -> f64 {
+  run_cli(&[String::from("getnetworkinfo")])
+    .ok()
+    .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+    .and_then(|info| info["relayfee"].as_f64())
+    .unwrap_or(FALLBACK_FEE_RATE)
+}
+
+/// Ask the node for a fee rate, trying `estimatesmartfee` first and then
+/// the older `estimatefee`. Never returns less than the relay fee.
+pub fn node_fee_rate(conf_target: u32) -> FeeEstimate {
+  let target = conf_target.max(1);
+  let mut estimate: Option<(f64, &str)> = None;
+
+  if let Ok(raw) = run_cli(&[String::from("estimatesmartfee"), target.to_string()]) {
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(&raw) {
+      if let Some(rate) = value["feerate"].as_f64().filter(|r| *r > 0.0) {
+        estimate = Some((rate, "estimatesmartfee"));
+      }
+    }
+  }
+
+  if estimate.is_none() {
+    if let Ok(raw) = run_cli(&[String::from("estimatefee"), target.to_string()]) {
+      if let Some(rate) = raw.trim().parse::<f64>().ok().filter(|r| *r > 0.0) {
+        estimate = Some((rate, "estimatefee"));
+      }
+    }
+  }
+
+  let relay = relay_fee_rate();
+  let (rate, source) = estimate.unwrap_or((FALLBACK_FEE_RATE, "fallback"));
+  FeeEstimate {
+    fee_rate: rate.max(relay),
+    source: source.to_string(),
+    conf_target: target,
+  }
+}
+
+/// Custom rate wins over the node estimate
+pub fn resolve_fee_rate(fee_rate: Option<f64>, conf_target: Option<u32>) -> Result<FeeEstimate, String> {
+  match fee_rate {
+    Some(rate) if !rate.is_finite() || rate <= 0.0 => Err("Fee rate must be a positive number".to_string()),
+    Some(rate) => Ok(FeeEstimate {
+      fee_rate: rate,
+      source: "custom".to_string(),
+      conf_target: conf_target.unwrap_or(DEFAULT_CONF_TARGET),
+    }),
+    None => Ok(node_fee_rate(conf_target.unwrap_or(DEFAULT_CONF_TARGET))),
+  }
+}
+
+pub fn max_fee_sats() -> u64 {
+  let max_fee = load_app_settings()
+    .ok()
+    .and_then(|s| s.max_fee)
+    .filter(|f| *f > 0.0)
+    .unwrap_or(DEFAULT_MAX_FEE);
+  to_sats(max_fee)
+}
+
+pub fn check_absurd_fee(fee_sats: u64) -> Result<(), String> {
+  let limit = max_fee_sats();
+  if fee_sats > limit {
+    return Err(format!(
+      "Fee of {} HEMP exceeds the maximum allowed fee of {} HEMP",
+      format_sats(fee_sats),
+      format_sats(limit)
+    ));
+  }
+  Ok(())
+}
+
+/// Look up the value of each input, from the wallet's UTXO set first and
+/// `gettxout` for anything not listed there (e.g. locked coins).
+pub fn input_values(inputs: &[RawTxInput]) -> Result<Vec<u64>, String> {
+  let raw = run_cli(&[
+    String::from("listunspent"),
+    String::from("0"),
+    String::from("9999999"),
+  ])?;
+  let utxos: Vec<UtxoItem> = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  let known: HashMap<(String, u64), f64> = utxos
+    .into_iter()
+    .map(|u| ((u.txid, u.vout), u.amount))
+    .collect();
+
+  let mut values = Vec::new();
+  for input in inputs {
+    if let Some(amount) = known.get(&(input.txid.clone(), input.vout)) {
+      values.push(to_sats(*amount));
+      continue;
+    }
+    let out_raw = run_cli(&[
+      String::from("gettxout"),
+      input.txid.clone(),
+      input.vout.to_string(),
+      String::from("true"),
+    ])?;
+    let out: serde_json::Value = serde_json::from_str(&out_raw)
+      .map_err(|_| format!("Input {}:{} is spent or unknown", input.txid, input.vout))?;
+    let amount = out["value"]
+      .as_f64()
+      .ok_or_else(|| format!("Input {}:{} is spent or unknown", input.txid, input.vout))?;
+    values.push(to_sats(amount));
+  }
+  Ok(values)
+}
+
+/// Work out fee and change for a set of inputs and outputs. The change
+/// output is only added when it is above dust; otherwise the remainder is
+/// paid as fee (and still checked against the absurd fee limit).
+pub fn plan_fee(
+  input_total: u64,
+  input_count: usize,
+  outputs: &HashMap<String, String>,
+  fee_rate: f64,
+  change_address: Option<String>,
+) -> Result<TxFeePlan, String> {
+  plan_fee_sized(input_total, input_count as u64 * P2PKH_INPUT_SIZE, outputs, fee_rate, change_address)
+}
+
+/// Same as `plan_fee` for inputs that are not plain P2PKH (e.g. multisig),
+/// taking the total size of all inputs in bytes
+pub fn plan_fee_sized(
+  input_total: u64,
+  input_size: u64,
+  outputs: &HashMap<String, String>,
+  fee_rate: f64,
+  change_address: Option<String>,
+) -> Result<TxFeePlan, String> {
+  let mut output_total = 0u64;
+  let mut planned = HashMap::new();
+  for (addr, amount) in outputs {
+    let sats = parse_amount_sats(amount)?;
+    if sats == 0 {
+      return Err(format!("Output amount for {} must be greater than zero", addr));
+    }
+    output_total += sats;
+    planned.insert(addr.clone(), format_sats(sats));
+  }
+
+  if output_total > input_total {
+    return Err(format!(
+      "Outputs ({} HEMP) exceed selected inputs ({} HEMP)",
+      format_sats(output_total),
+      format_sats(input_total)
+    ));
+  }
+  let leftover = input_total - output_total;
+
+  let size_no_change = TX_OVERHEAD_SIZE + input_size + planned.len() as u64 * P2PKH_OUTPUT_SIZE;
+  let fee_no_change = fee_for_size(size_no_change, fee_rate);
+  if leftover < fee_no_change {
+    return Err(format!(
+      "Insufficient inputs: need {} HEMP for fees, only {} HEMP left after outputs",
+      format_sats(fee_no_change),
+      format_sats(leftover)
+    ));
+  }
+
+  let size_with_change = size_no_change + P2PKH_OUTPUT_SIZE;
+  let fee_with_change = fee_for_size(size_with_change, fee_rate);
+  let change = leftover.saturating_sub(fee_with_change);
+
+  let (size, fee, change, change_address) = match change_address {
+    Some(addr) if change >= DUST_THRESHOLD_SATS => {
+      if planned.contains_key(&addr) {
+        return Err("Change address is already used as an output".to_string());
+      }
+      planned.insert(addr.clone(), format_sats(change));
+      (size_with_change, fee_with_change, change, Some(addr))
+    }
+    _ => (size_no_change, leftover, 0, None),
+  };
+
+  if size > MAX_STANDARD_TX_SIZE {
+    return Err(format!(
+      "Transaction too large ({} bytes, limit {}); use fewer inputs",
+      size, MAX_STANDARD_TX_SIZE
+    ));
+  }
+  check_absurd_fee(fee)?;
+
+  Ok(TxFeePlan {
+    size,
+    fee_rate,
+    fee: format_sats(fee),
+    input_total: format_sats(input_total),
+    output_total: format_sats(output_total),
+    change: format_sats(change),
+    change_address,
+    outputs: planned,
+  })
+}
+
+/// Full planning step used by both the preview and the broadcast path
+pub fn plan_advanced_transaction(
+  inputs: &[RawTxInput],
+  outputs: &HashMap<String, String>,
+  fee_rate: Option<f64>,
+  conf_target: Option<u32>,
+) -> Result<TxFeePlan, String> {
+  if inputs.is_empty() {
+    return Err("At least one input is required".to_string());
+  }
+  if outputs.is_empty() {
+    return Err("At least one output is required".to_string());
+  }
+  let estimate = resolve_fee_rate(fee_rate, conf_target)?;
+  let input_total: u64 = input_values(inputs)?.iter().sum();
+
+  // Only fetch a change address if there is something worth sending back
+  let output_total: u64 = outputs
+    .values()
+    .map(|a| parse_amount_sats(a))
+    .collect::<Result<Vec<u64>, String>>()?
+    .iter()
+    .sum();
+  let min_fee = fee_for_size(estimate_tx_size(inputs.len(), outputs.len() + 1), estimate.fee_rate);
+  let change_address = if input_total > output_total + min_fee + DUST_THRESHOLD_SATS {
+    Some(run_cli(&[String::from("getrawchangeaddress")])?)
+  } else {
+    None
+  };
+
+  plan_fee(input_total, inputs.len(), outputs, estimate.fee_rate, change_address)
+}
+
+/// `validateaddress` ismine, for addresses that come back from the webview
+pub fn is_my_address(address: &str) -> Result<bool, String> {
+  let raw = run_cli(&[String::from("validateaddress"), address.to_string()])?;
+  let info: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  Ok(info["ismine"].as_bool().unwrap_or(false))
+}
+
+/// Rebuild the plan the user confirmed, with its fee rate and change
+/// address instead of a fresh estimate and change address, and refuse if
+/// anything differs from what was shown.
+pub fn replan_confirmed(
+  inputs: &[RawTxInput],
+  outputs: &HashMap<String, String>,
+  confirmed: &TxFeePlan,
+) -> Result<TxFeePlan, String> {
+  if inputs.is_empty() {
+    return Err("At least one input is required".to_string());
+  }
+  if let Some(addr) = &confirmed.change_address {
+    if !is_my_address(addr)? {
+      return Err(format!("Change address {} does not belong to this wallet", addr));
+    }
+  }
+  let input_total: u64 = input_values(inputs)?.iter().sum();
+  let plan = plan_fee(input_total, inputs.len(), outputs, confirmed.fee_rate, confirmed.change_address.clone())?;
+  if plan.fee != confirmed.fee || plan.change != confirmed.change || plan.outputs != confirmed.outputs {
+    return Err(format!(
+      "Transaction changed since it was confirmed (fee {} HEMP, change {} HEMP). Review it again.",
+      plan.fee, plan.change
+    ));
+  }
+  Ok(plan)
+}
+
+#[tauri::command]
+pub fn estimate_fee_rate(conf_target: Option<u32>) -> Result<FeeEstimate, String> {
+  ensure_config()?;
+  Ok(node_fee_rate(conf_target.unwrap_or(DEFAULT_CONF_TARGET)))
+}
+
+#[tauri::command]
+pub fn estimate_tx_fee(inputs: u32, outputs: u32, fee_rate: Option<f64>, conf_target: Option<u32>) -> Result<String, String> {
+  ensure_config()?;
+  let estimate = resolve_fee_rate(fee_rate, conf_target)?;
+  let size = estimate_tx_size(inputs as usize, outputs as usize);
+  Ok(format_sats(fee_for_size(size, estimate.fee_rate)))
+}
+
+#[tauri::command]
+pub fn preview_advanced_transaction(
+  inputs: Vec<RawTxInput>,
+  outputs: HashMap<String, String>,
+  fee_rate: Option<f64>,
+  conf_target: Option<u32>,
+) -> Result<TxFeePlan, String> {
+  ensure_config()?;
+  plan_advanced_transaction(&inputs, &outputs, fee_rate, conf_target)
+}
@@ -14,6 +14,8 @@ use std::os::unix::fs::PermissionsExt;
 // Import local modules
 use crate::modules::models::{ConfigPaths, DataFolderInfo, BinaryStatus, AddressBookEntry, AppSettings};
 use crate::modules::utils::{resolve_bin, bin_name, calculate_dir_size, format_size};
+use crate::modules::safety_backup::snapshot_wallet;
+use crate::modules::wallets::active_wallet;
 
 pub fn data_dir() -> Result<PathBuf, String> {
   if cfg!(windows) {
@@ -191,7 +193,8 @@ pub fn get_data_folder_info() -> Result<DataFolderInfo, String> {
   let dir = data_dir()?;
   let folder_exists = dir.exists();
   let config_exists = dir.join("hemp.conf").exists();
-  let wallet_exists = dir.join("wallet.dat").exists();
+  let active_wallet = active_wallet();
+  let wallet_exists = dir.join(&active_wallet).exists();
   
   let size_bytes = if folder_exists { calculate_dir_size(&dir) } else { 0 };
   let size_display = format_size(size_bytes);
@@ -203,6 +206,7 @@ pub fn get_data_folder_info() -> Result<DataFolderInfo, String> {
     config_exists,
     wallet_exists,
     folder_exists,
+    active_wallet,
   })
 }
 
@@ -262,6 +266,30 @@ fn read_log_tail(path: &Path, max_lines: usize) -> Result<String, String> {
   Ok(lines[start..].join("\n"))
 }
 
+/// Create a new file readable by the owner only, for key material that has
+/// to touch the disk
+pub fn write_private_file(path: &Path, data: &[u8]) -> Result<(), String> {
+  let mut options = fs::OpenOptions::new();
+  options.write(true).create_new(true);
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::OpenOptionsExt;
+    options.mode(0o600);
+  }
+  let mut file = options.open(path).map_err(|e| e.to_string())?;
+  std::io::Write::write_all(&mut file, data).map_err(|e| e.to_string())
+}
+
+/// Overwrite a file holding key material with zeros before removing it
+pub fn wipe_file(path: &Path) -> Result<(), String> {
+  if !path.exists() {
+    return Ok(());
+  }
+  let len = fs::metadata(path).map_err(|e| e.to_string())?.len() as usize;
+  fs::write(path, vec![0u8; len]).map_err(|e| e.to_string())?;
+  fs::remove_file(path).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn read_log(lines: Option<u32>) -> Result<String, String> {
   let dir = data_dir()?;
@@ -393,6 +421,7 @@ pub fn extract_snapshot(archive_path: String) -> Result<String, String> {
   }
   
   let dest_dir = data_dir()?;
+  snapshot_wallet("snapshot")?;
   
   // Create a temp extraction folder
   let temp_extract = dest_dir.join("_snapshot_temp");
@@ -0,0 +1,191 @@
+// Import local modules
+use crate::modules::models::{MessageVerification, SignedMessage};
+use crate::modules::commands::{run_cli, with_unlocked_wallet};
+use crate::modules::files::ensure_config;
+
+// Signed messages are exchanged in the armoured layout used by Bitcoin
+// Core-derived wallets:
+//
+//   -----BEGIN HEMP0X SIGNED MESSAGE-----
+//   <message>
+//   -----BEGIN SIGNATURE-----
+//   <address>
+//   <signature>
+//   -----END HEMP0X SIGNED MESSAGE-----
+//
+// The parser also accepts other coin labels and Electrum's
+// "Address: ..." header style, and any number of blocks in one text
+// (one per address for proof-of-reserves). Message lines are kept byte
+// for byte; lines starting with '-' are dash-escaped ("- ") as in
+// RFC 4880 so they cannot be mistaken for armour lines.
+
+pub const ARMOR_LABEL: &str = "HEMP0X";
+
+fn dash_escape(message: &str) -> String {
+  message
+    .split('\n')
+    .map(|l| if l.starts_with('-') { format!("- {}", l) } else { l.to_string() })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+pub fn armor_message(msg: &SignedMessage) -> String {
+  format!(
+    "-----BEGIN {label} SIGNED MESSAGE-----\n{}\n-----BEGIN SIGNATURE-----\n{}\n{}\n-----END {label} SIGNED MESSAGE-----\n",
+    dash_escape(&msg.message),
+    msg.address,
+    msg.signature,
+    label = ARMOR_LABEL
+  )
+}
+
+fn is_begin_message(line: &str) -> bool {
+  line.starts_with("-----BEGIN ") && line.ends_with(" SIGNED MESSAGE-----")
+}
+
+fn is_begin_signature(line: &str) -> bool {
+  line.starts_with("-----BEGIN ") && line.ends_with("SIGNATURE-----")
+}
+
+pub fn parse_armored(text: &str) -> Result<Vec<SignedMessage>, String> {
+  let text = text.replace("\r\n", "\n");
+  // Only armour lines are trimmed; message lines must stay as signed
+  let mut lines = text.split('\n');
+  let mut messages = Vec::new();
+
+  while let Some(line) = lines.by_ref().find(|l| is_begin_message(l.trim())) {
+    let header = line.trim();
+    let mut body = Vec::new();
+    let mut found_signature = false;
+    for l in lines.by_ref() {
+      if is_begin_signature(l.trim()) {
+        found_signature = true;
+        break;
+      }
+      body.push(l.strip_prefix("- ").unwrap_or(l));
+    }
+    if !found_signature {
+      return Err(format!("Missing signature after {}", header));
+    }
+
+    let mut address = None;
+    let mut signature = None;
+    let mut closed = false;
+    for l in lines.by_ref() {
+      let l = l.trim();
+      if l.starts_with("-----END ") {
+        closed = true;
+        break;
+      }
+      if l.is_empty() {
+        continue;
+      }
+      match l.split_once(": ") {
+        Some((key, value)) if key.eq_ignore_ascii_case("address") => address = Some(value.trim().to_string()),
+        // Other headers (Version:, Comment:) carry nothing we need
+        Some((key, _)) if !key.contains(' ') && !key.contains('=') => {}
+        _ if address.is_none() => address = Some(l.to_string()),
+        _ if signature.is_none() => signature = Some(l.to_string()),
+        _ => return Err("Unexpected extra line in signature block".to_string()),
+      }
+    }
+    if !closed {
+      return Err("Signed message is not terminated with an END line".to_string());
+    }
+
+    messages.push(SignedMessage {
+      address: address.ok_or("Signature block has no address")?,
+      message: body.join("\n"),
+      signature: signature.ok_or("Signature block has no signature")?,
+    });
+  }
+
+  if messages.is_empty() {
+    return Err("No signed message found".to_string());
+  }
+  Ok(messages)
+}
+
+fn verify(msg: &SignedMessage) -> MessageVerification {
+  let result = run_cli(&[
+    String::from("verifymessage"),
+    msg.address.clone(),
+    msg.signature.clone(),
+    msg.message.clone(),
+  ]);
+  let (valid, error) = match result {
+    Ok(raw) => (raw.trim() == "true", None),
+    Err(e) => (false, Some(e)),
+  };
+  MessageVerification {
+    address: msg.address.clone(),
+    message: msg.message.clone(),
+    signature: msg.signature.clone(),
+    valid,
+    error,
+  }
+}
+
+/// Sign with the key of one of this wallet's addresses
+#[tauri::command]
+pub fn sign_message(address: String, message: String, wallet_passphrase: Option<String>) -> Result<SignedMessage, String> {
+  ensure_config()?;
+  let address = address.trim().to_string();
+  let signature = with_unlocked_wallet(wallet_passphrase.as_deref(), || {
+    run_cli(&[String::from("signmessage"), address.clone(), message.clone()])
+  })?;
+  Ok(SignedMessage {
+    address,
+    message,
+    signature: signature.trim().to_string(),
+  })
+}
+
+/// Sign with a WIF key that is not in the wallet. The node cannot derive
+/// the address from the key, so the caller names it and the signature is
+/// verified against it before being returned.
+#[tauri::command]
+pub fn sign_message_with_privkey(priv_key: String, address: String, message: String) -> Result<SignedMessage, String> {
+  ensure_config()?;
+  let signature = run_cli(&[String::from("signmessagewithprivkey"), priv_key.trim().to_string(), message.clone()])?;
+  let signed = SignedMessage {
+    address: address.trim().to_string(),
+    message,
+    signature: signature.trim().to_string(),
+  };
+  if !verify(&signed).valid {
+    return Err(format!("The private key does not belong to {}", signed.address));
+  }
+  Ok(signed)
+}
+
+#[tauri::command]
+pub fn verify_message(address: String, signature: String, message: String) -> Result<MessageVerification, String> {
+  ensure_config()?;
+  Ok(verify(&SignedMessage {
+    address: address.trim().to_string(),
+    message,
+    signature: signature.trim().to_string(),
+  }))
+}
+
+/// Armoured text for one or more signed messages, ready to save or paste
+#[tauri::command]
+pub fn export_signed_messages(messages: Vec<SignedMessage>) -> Result<String, String> {
+  if messages.is_empty() {
+    return Err("Nothing to export".to_string());
+  }
+  Ok(messages.iter().map(armor_message).collect::<Vec<String>>().join("\n"))
+}
+
+#[tauri::command]
+pub fn parse_signed_messages(text: String) -> Result<Vec<SignedMessage>, String> {
+  parse_armored(&text)
+}
+
+/// Verify every armoured block in `text`
+#[tauri::command]
+pub fn verify_signed_messages(text: String) -> Result<Vec<MessageVerification>, String> {
+  ensure_config()?;
+  Ok(parse_armored(&text)?.iter().map(verify).collect())
+}
@@ -0,0 +1,215 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+use tauri::Emitter;
+
+// Import local modules
+use crate::modules::models::AssetMessage;
+use crate::modules::utils::txid_from;
+use crate::modules::commands::run_cli;
+use crate::modules::files::{data_dir, ensure_config};
+use crate::modules::naming::onchain_ipfs_hash;
+
+// Asset messages are IPFS hashes broadcast on a channel: the owner token
+// (`ASSET!`) or a message channel (`ASSET~NAME`). The node keeps the
+// messages of subscribed channels; which ones were already seen is tracked
+// in `messages_seen.json` so the watcher can announce new ones with an
+// `asset-messages` event.
+
+const WATCH_INTERVAL_SECS: u64 = 60;
+
+static WATCHER_RUNNING: AtomicBool = AtomicBool::new(false);
+
+fn seen_path() -> Result<PathBuf, String> {
+  Ok(data_dir()?.join("messages_seen.json"))
+}
+
+fn load_seen() -> Result<Vec<String>, String> {
+  let path = seen_path()?;
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+  let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+  let seen: Vec<String> = serde_json::from_str(&content).unwrap_or_default();
+  Ok(seen)
+}
+
+fn save_seen(seen: &[String]) -> Result<(), String> {
+  let path = seen_path()?;
+  let content = serde_json::to_string_pretty(seen).map_err(|e| e.to_string())?;
+  fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+fn is_channel(name: &str) -> bool {
+  (name.ends_with('!') && name.len() > 1) || name.contains('~')
+}
+
+/// Messages of all subscribed channels, newest first
+fn fetch_messages() -> Result<Vec<AssetMessage>, String> {
+  let raw = run_cli(&[String::from("viewallmessages")])?;
+  let value: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  // Keys are display labels ("Asset Name", "Block Height", ...)
+  let text = |row: &serde_json::Value, key: &str| -> Option<String> {
+    row.get(key).map(|v| v.as_str().map(|s| s.to_string()).unwrap_or_else(|| v.to_string()))
+  };
+  let seen = load_seen()?;
+  let mut messages: Vec<AssetMessage> = value
+    .as_array()
+    .into_iter()
+    .flatten()
+    .map(|row| {
+      let asset_name = text(row, "Asset Name").unwrap_or_default();
+      let ipfs_hash = text(row, "Message").unwrap_or_default();
+      let block_height = row.get("Block Height").and_then(|v| v.as_u64()).unwrap_or(0);
+      let id = format!("{}:{}:{}", asset_name, block_height, ipfs_hash);
+      AssetMessage {
+        is_new: !seen.contains(&id),
+        id,
+        asset_name,
+        ipfs_hash,
+        time: text(row, "Time").unwrap_or_default(),
+        block_height,
+        status: text(row, "Status").unwrap_or_default(),
+        expire_time: text(row, "Expire UTC Time").or_else(|| text(row, "Expire Time")),
+      }
+    })
+    .collect();
+  messages.sort_by_key(|m| std::cmp::Reverse(m.block_height));
+  Ok(messages)
+}
+
+fn subscribed_channels() -> Result<Vec<String>, String> {
+  let raw = run_cli(&[String::from("viewallmessagechannels")])?;
+  serde_json::from_str::<Vec<String>>(&raw).map_err(|e| e.to_string())
+}
+
+/// Check for new messages in the background and emit `asset-messages`
+/// with the ones not announced before
+pub fn start_message_watcher(app_handle: tauri::AppHandle) {
+  if WATCHER_RUNNING.swap(true, Ordering::SeqCst) {
+    return;
+  }
+  thread::spawn(move || {
+    let mut announced: Vec<String> = Vec::new();
+    loop {
+      thread::sleep(Duration::from_secs(WATCH_INTERVAL_SECS));
+      let fresh: Vec<AssetMessage> = match fetch_messages() {
+        Ok(messages) => messages
+          .into_iter()
+          .filter(|m| m.is_new && !announced.contains(&m.id))
+          .collect(),
+        // Node offline or messaging disabled
+        Err(_) => continue,
+      };
+      if !fresh.is_empty() {
+        announced.extend(fresh.iter().map(|m| m.id.clone()));
+        let _ = app_handle.emit("asset-messages", fresh);
+      }
+    }
+  });
+}
+
+#[tauri::command]
+pub fn get_asset_messages() -> Result<Vec<AssetMessage>, String> {
+  ensure_config()?;
+  fetch_messages()
+}
+
+/// Mark messages as read; all current messages when `ids` is empty
+#[tauri::command]
+pub fn mark_messages_read(ids: Vec<String>) -> Result<(), String> {
+  ensure_config()?;
+  let mut seen = load_seen()?;
+  let ids = if ids.is_empty() {
+    fetch_messages()?.into_iter().map(|m| m.id).collect()
+  } else {
+    ids
+  };
+  for id in ids {
+    if !seen.contains(&id) {
+      seen.push(id);
+    }
+  }
+  save_seen(&seen)
+}
+
+/// Delete all messages the node has stored
+#[tauri::command]
+pub fn clear_asset_messages() -> Result<(), String> {
+  ensure_config()?;
+  run_cli(&[String::from("clearmessages")])?;
+  save_seen(&[])
+}
+
+#[tauri::command]
+pub fn list_message_channels() -> Result<Vec<String>, String> {
+  ensure_config()?;
+  subscribed_channels()
+}
+
+#[tauri::command]
+pub fn subscribe_channel(channel: String) -> Result<(), String> {
+  ensure_config()?;
+  let channel = channel.trim().to_string();
+  if !is_channel(&channel) {
+    return Err("Channels are owner tokens (ASSET!) or message channels (ASSET~NAME)".to_string());
+  }
+  run_cli(&[String::from("subscribetochannel"), channel])?;
+  Ok(())
+}
+
+#[tauri::command]
+pub fn unsubscribe_channel(channel: String) -> Result<(), String> {
+  ensure_config()?;
+  run_cli(&[String::from("unsubscribefromchannel"), channel.trim().to_string()])?;
+  Ok(())
+}
+
+/// Subscribe to the owner channel of every asset in the wallet. Returns
+/// the channels that were added.
+#[tauri::command]
+pub fn subscribe_held_asset_channels() -> Result<Vec<String>, String> {
+  ensure_config()?;
+  let raw = run_cli(&[String::from("listmyassets")])?;
+  let value: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  let current = subscribed_channels()?;
+  let mut added = Vec::new();
+  for name in value.as_object().into_iter().flat_map(|o| o.keys()) {
+    // Owner announcements come from the root asset's owner token
+    let root = name.split(['/', '#', '~']).next().unwrap_or(name).trim_end_matches('!').trim_start_matches('$');
+    let channel = format!("{}!", root);
+    if root.is_empty() || current.contains(&channel) || added.contains(&channel) {
+      continue;
+    }
+    run_cli(&[String::from("subscribetochannel"), channel.clone()])?;
+    added.push(channel);
+  }
+  Ok(added)
+}
+
+/// Broadcast an IPFS hash on a channel this wallet holds the token for.
+/// `expire_time` is a unix timestamp after which clients drop the message.
+#[tauri::command]
+pub fn send_asset_message(channel: String, ipfs_hash: String, expire_time: Option<u64>) -> Result<String, String> {
+  ensure_config()?;
+  let channel = channel.trim().to_string();
+  if !is_channel(&channel) {
+    return Err("Broadcast from an owner token (ASSET!) or a message channel (ASSET~NAME)".to_string());
+  }
+  if ipfs_hash.trim().is_empty() {
+    return Err("Message IPFS hash is required".to_string());
+  }
+  let ipfs_hash = onchain_ipfs_hash(&ipfs_hash)?;
+  let raw = run_cli(&[String::from("listmyassets"), channel.clone()])?;
+  let held: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  if held.get(&channel).and_then(|v| v.as_f64()).unwrap_or(0.0) <= 0.0 {
+    return Err(format!("Broadcasting on {} needs the {} token in this wallet", channel, channel));
+  }
+  let mut args = vec![String::from("sendmessage"), channel, ipfs_hash];
+  if let Some(expire) = expire_time.filter(|t| *t > 0) {
+    args.push(expire.to_string());
+  }
+  Ok(txid_from(&run_cli(&args)?))
+}
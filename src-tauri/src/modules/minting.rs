@@ -0,0 +1,477 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+use chrono::Local;
+use tauri::Emitter;
+use uuid::Uuid;
+
+// Import local modules
+use crate::modules::models::{MintItem, MintJob, MintProgress};
+use crate::modules::utils::txid_from;
+use crate::modules::commands::run_cli;
+use crate::modules::files::{data_dir, ensure_config};
+use crate::modules::naming::{check_asset_name, expect_asset_kind, onchain_ipfs_hash, AssetKind};
+use crate::modules::burns::take_quote;
+
+// Unique assets (NFTs) minted from a CSV or JSON manifest of tag, IPFS
+// hash and destination. `issueunique` sends to one address per call, so
+// items are grouped by destination and cut into batches. Jobs are kept in
+// `mint_jobs.json` with the state of every item; an interrupted job picks
+// up at the first batch that was not minted.
+//
+// Job status: ready -> minting -> completed, or paused / failed (both
+// resumable). Item status: pending, minted, skipped (already on chain).
+
+pub const DEFAULT_MINT_BATCH: u32 = 50;
+/// Keeps a batch well below the standard transaction size
+pub const MAX_MINT_BATCH: u32 = 200;
+/// Manifest problems listed before the rest are summarised
+const MAX_REPORTED_ERRORS: usize = 20;
+/// Each batch spends the owner token change of the one before; the node
+/// refuses a longer unconfirmed chain, so the minter waits for a block
+const CHAIN_WAIT_SECS: u64 = 60;
+
+static MINTER_RUNNING: AtomicBool = AtomicBool::new(false);
+static STOP_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+fn mint_jobs_path() -> Result<PathBuf, String> {
+  Ok(data_dir()?.join("mint_jobs.json"))
+}
+
+fn load_mint_jobs() -> Result<Vec<MintJob>, String> {
+  let path = mint_jobs_path()?;
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+  let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+  let jobs: Vec<MintJob> = serde_json::from_str(&content).unwrap_or_default();
+  Ok(jobs)
+}
+
+fn save_mint_jobs(jobs: &[MintJob]) -> Result<(), String> {
+  let path = mint_jobs_path()?;
+  let content = serde_json::to_string_pretty(jobs).map_err(|e| e.to_string())?;
+  fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+fn update_job(job: &MintJob) -> Result<(), String> {
+  let mut jobs = load_mint_jobs()?;
+  match jobs.iter_mut().find(|j| j.id == job.id) {
+    Some(existing) => *existing = job.clone(),
+    None => jobs.push(job.clone()),
+  }
+  save_mint_jobs(&jobs)
+}
+
+fn find_job(id: &str) -> Result<MintJob, String> {
+  load_mint_jobs()?
+    .into_iter()
+    .find(|j| j.id == id)
+    .ok_or_else(|| format!("Unknown mint job {}", id))
+}
+
+/// Tag, IPFS hash and address of each manifest row, with its line number
+type ManifestRow = (usize, String, String, String);
+
+/// CSV with an optional `tag,ipfs,address` header (any column order when
+/// the header is there). Blank lines and `#` comments are skipped.
+fn parse_csv(content: &str) -> Result<Vec<ManifestRow>, String> {
+  let mut columns = [0usize, 1, 2];
+  let mut rows = Vec::new();
+  for (i, line) in content.lines().enumerate() {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+    let cells: Vec<String> = line.split([',', ';', '\t']).map(|c| c.trim().trim_matches('"').to_string()).collect();
+    if rows.is_empty() && cells.iter().any(|c| c.eq_ignore_ascii_case("tag")) {
+      let position = |names: &[&str]| cells.iter().position(|c| names.iter().any(|n| c.eq_ignore_ascii_case(n)));
+      columns = [
+        position(&["tag"]).ok_or("The header has no tag column")?,
+        position(&["ipfs", "ipfs_hash", "hash"]).unwrap_or(usize::MAX),
+        position(&["address", "to_address", "destination"]).unwrap_or(usize::MAX),
+      ];
+      continue;
+    }
+    let cell = |col: usize| cells.get(col).cloned().unwrap_or_default();
+    rows.push((i + 1, cell(columns[0]), cell(columns[1]), cell(columns[2])));
+  }
+  Ok(rows)
+}
+
+/// A JSON array of `{ "tag", "ipfs", "address" }` objects
+fn parse_json(content: &str) -> Result<Vec<ManifestRow>, String> {
+  let value: serde_json::Value = serde_json::from_str(content).map_err(|e| format!("Manifest is not valid JSON: {}", e))?;
+  let entries = value.as_array().ok_or("A JSON manifest is an array of { tag, ipfs, address } objects")?;
+  let text = |entry: &serde_json::Value, keys: &[&str]| {
+    keys.iter().find_map(|k| entry[*k].as_str()).unwrap_or("").trim().to_string()
+  };
+  Ok(entries
+    .iter()
+    .enumerate()
+    .map(|(i, entry)| {
+      (
+        i + 1,
+        text(entry, &["tag"]),
+        text(entry, &["ipfs", "ipfs_hash", "hash"]),
+        text(entry, &["address", "to_address", "destination"]),
+      )
+    })
+    .collect())
+}
+
+fn read_manifest(path: &Path) -> Result<Vec<ManifestRow>, String> {
+  let content = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+  let is_json = path.extension().map(|e| e.eq_ignore_ascii_case("json")).unwrap_or(false)
+    || content.trim_start().starts_with('[');
+  let rows = if is_json { parse_json(&content)? } else { parse_csv(&content)? };
+  if rows.is_empty() {
+    return Err("The manifest has no rows".to_string());
+  }
+  Ok(rows)
+}
+
+fn owner_token_held(root_name: &str) -> bool {
+  let owner = format!("{}!", root_name);
+  run_cli(&[String::from("listmyassets"), owner.clone()])
+    .ok()
+    .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+    .and_then(|v| v.get(&owner).and_then(|b| b.as_f64()))
+    .map(|b| b > 0.0)
+    .unwrap_or(false)
+}
+
+fn is_valid_address(address: &str) -> bool {
+  run_cli(&[String::from("validateaddress"), address.to_string()])
+    .ok()
+    .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+    .and_then(|v| v["isvalid"].as_bool())
+    .unwrap_or(false)
+}
+
+fn asset_exists(name: &str) -> bool {
+  run_cli(&[String::from("getassetdata"), name.to_string()]).is_ok()
+}
+
+/// Check every row, collecting all problems instead of stopping at the first
+fn validate_rows(root_name: &str, rows: Vec<ManifestRow>, default_address: &str) -> Result<Vec<MintItem>, String> {
+  let mut errors = Vec::new();
+  let mut seen: HashMap<String, usize> = HashMap::new();
+  let mut addresses: HashMap<String, bool> = HashMap::new();
+  let mut items = Vec::new();
+  for (line, tag, ipfs, address) in rows {
+    let name = format!("{}#{}", root_name, tag);
+    if let Err(e) = check_asset_name(&name) {
+      errors.push(format!("Row {}: {}", line, e));
+      continue;
+    }
+    if let Some(first) = seen.insert(tag.clone(), line) {
+      errors.push(format!("Row {}: tag {} repeats row {}", line, tag, first));
+      continue;
+    }
+    let ipfs = match onchain_ipfs_hash(&ipfs) {
+      Ok(hash) => hash,
+      Err(e) => {
+        errors.push(format!("Row {}: {}", line, e));
+        continue;
+      }
+    };
+    let address = if address.is_empty() { default_address.to_string() } else { address };
+    if !address.is_empty() && !*addresses.entry(address.clone()).or_insert_with(|| is_valid_address(&address)) {
+      errors.push(format!("Row {}: invalid address {}", line, address));
+      continue;
+    }
+    if asset_exists(&name) {
+      errors.push(format!("Row {}: {} already exists", line, name));
+      continue;
+    }
+    items.push(MintItem {
+      tag,
+      ipfs,
+      address,
+      batch: 0,
+      status: String::from("pending"),
+      txid: None,
+      error: None,
+    });
+  }
+  if !errors.is_empty() {
+    let more = errors.len().saturating_sub(MAX_REPORTED_ERRORS);
+    let mut message = format!("{} problem(s) in the manifest: {}", errors.len(), errors[..errors.len() - more].join("; "));
+    if more > 0 {
+      message.push_str(&format!("; and {} more", more));
+    }
+    return Err(message);
+  }
+  Ok(items)
+}
+
+/// Group items by destination (in manifest order) and number the batches.
+/// A call takes IPFS hashes for all of its tags or none, so items with and
+/// without a hash never share a batch.
+fn assign_batches(items: &mut [MintItem], batch_size: u32) -> u32 {
+  let group = |item: &MintItem| (item.address.clone(), item.ipfs.is_empty());
+  let mut order: Vec<(String, bool)> = Vec::new();
+  for item in items.iter() {
+    if !order.contains(&group(item)) {
+      order.push(group(item));
+    }
+  }
+  let mut batch = 0;
+  for key in order {
+    let mut in_batch = 0;
+    for item in items.iter_mut().filter(|i| group(i) == key) {
+      if in_batch == batch_size {
+        batch += 1;
+        in_batch = 0;
+      }
+      item.batch = batch;
+      in_batch += 1;
+    }
+    batch += 1;
+  }
+  batch
+}
+
+fn pending_count(job: &MintJob) -> u32 {
+  job.items.iter().filter(|i| i.status == "pending").count() as u32
+}
+
+fn progress(job: &MintJob, batch: u32, txid: Option<String>) -> MintProgress {
+  MintProgress {
+    job_id: job.id.clone(),
+    status: job.status.clone(),
+    minted: job.items.iter().filter(|i| i.status != "pending").count() as u32,
+    total: job.items.len() as u32,
+    batch,
+    batches: job.batches,
+    txid,
+    error: job.error.clone(),
+  }
+}
+
+fn is_chain_limit(error: &str) -> bool {
+  let error = error.to_ascii_lowercase();
+  error.contains("too-long-mempool-chain") || error.contains("too long mempool chain")
+}
+
+/// Mint one batch. Items already on chain (minted before an interruption
+/// was recorded) are skipped rather than failing the whole batch.
+fn mint_batch(job: &mut MintJob, batch: u32) -> Result<Option<String>, String> {
+  for item in job.items.iter_mut().filter(|i| i.batch == batch && i.status == "pending") {
+    if asset_exists(&format!("{}#{}", job.root_name, item.tag)) {
+      item.status = String::from("skipped");
+      item.error = Some(String::from("Already on chain; minted before the job was interrupted"));
+    }
+  }
+  let pending: Vec<&MintItem> = job.items.iter().filter(|i| i.batch == batch && i.status == "pending").collect();
+  if pending.is_empty() {
+    return Ok(None);
+  }
+  let tags: Vec<&str> = pending.iter().map(|i| i.tag.as_str()).collect();
+  let hashes: Vec<&str> = pending.iter().map(|i| i.ipfs.as_str()).collect();
+  let ipfs_json = if hashes.iter().any(|h| h.is_empty()) {
+    String::from("[]")
+  } else {
+    serde_json::to_string(&hashes).map_err(|e| e.to_string())?
+  };
+  let raw = run_cli(&[
+    String::from("issueunique"),
+    job.root_name.clone(),
+    serde_json::to_string(&tags).map_err(|e| e.to_string())?,
+    ipfs_json,
+    pending[0].address.clone(),
+  ])?;
+  let txid = txid_from(&raw);
+  for item in job.items.iter_mut().filter(|i| i.batch == batch && i.status == "pending") {
+    item.status = String::from("minted");
+    item.txid = Some(txid.clone());
+    item.error = None;
+  }
+  Ok(Some(txid))
+}
+
+/// Mint the pending batches of a job in the background, emitting
+/// `mint-progress` after each one
+fn spawn_minter(app_handle: tauri::AppHandle, mut job: MintJob) {
+  thread::spawn(move || {
+    let batches: Vec<u32> = {
+      let mut b: Vec<u32> = job.items.iter().filter(|i| i.status == "pending").map(|i| i.batch).collect();
+      b.sort_unstable();
+      b.dedup();
+      b
+    };
+    for batch in batches {
+      loop {
+        if STOP_REQUESTED.load(Ordering::SeqCst) {
+          job.status = String::from("paused");
+          let _ = update_job(&job);
+          let _ = app_handle.emit("mint-progress", progress(&job, batch, None));
+          MINTER_RUNNING.store(false, Ordering::SeqCst);
+          return;
+        }
+        match mint_batch(&mut job, batch) {
+          Ok(txid) => {
+            let _ = update_job(&job);
+            let _ = app_handle.emit("mint-progress", progress(&job, batch + 1, txid));
+            break;
+          }
+          Err(e) if is_chain_limit(&e) => {
+            let _ = app_handle.emit("mint-progress", MintProgress {
+              error: Some(String::from("Waiting for a block: too many unconfirmed batches")),
+              ..progress(&job, batch, None)
+            });
+            thread::sleep(Duration::from_secs(CHAIN_WAIT_SECS));
+          }
+          Err(e) => {
+            for item in job.items.iter_mut().filter(|i| i.batch == batch && i.status == "pending") {
+              item.error = Some(e.clone());
+            }
+            job.status = String::from("failed");
+            job.error = Some(format!("Batch {} of {}: {}", batch + 1, job.batches, e));
+            let _ = update_job(&job);
+            let _ = app_handle.emit("mint-progress", progress(&job, batch, None));
+            MINTER_RUNNING.store(false, Ordering::SeqCst);
+            return;
+          }
+        }
+      }
+    }
+    job.status = String::from("completed");
+    job.error = None;
+    let _ = update_job(&job);
+    let _ = app_handle.emit("mint-progress", progress(&job, job.batches, None));
+    MINTER_RUNNING.store(false, Ordering::SeqCst);
+  });
+}
+
+/// Read and validate a manifest into a job ready to mint. Nothing is sent:
+/// minting starts with `start_mint_job` and a confirmed cost quote.
+#[tauri::command]
+pub fn create_mint_job(
+  root_name: String,
+  manifest_path: String,
+  default_address: Option<String>,
+  batch_size: Option<u32>,
+) -> Result<MintJob, String> {
+  ensure_config()?;
+  let root_name = root_name.trim().to_string();
+  expect_asset_kind(&root_name, &[AssetKind::Root, AssetKind::Sub], "the parent of unique assets")?;
+  if !owner_token_held(&root_name) {
+    return Err(format!("Minting {}#... needs the {}! owner token in this wallet", root_name, root_name));
+  }
+  let batch_size = batch_size.unwrap_or(DEFAULT_MINT_BATCH);
+  if batch_size == 0 || batch_size > MAX_MINT_BATCH {
+    return Err(format!("Batch size must be between 1 and {}", MAX_MINT_BATCH));
+  }
+  let default_address = default_address.map(|a| a.trim().to_string()).unwrap_or_default();
+  let path = PathBuf::from(manifest_path.trim());
+  let mut items = validate_rows(&root_name, read_manifest(&path)?, &default_address)?;
+  let batches = assign_batches(&mut items, batch_size);
+
+  let job = MintJob {
+    id: Uuid::new_v4().to_string(),
+    root_name,
+    source: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+    batch_size,
+    batches,
+    status: String::from("ready"),
+    created: Local::now().timestamp() as u64,
+    items,
+    error: None,
+  };
+  update_job(&job)?;
+  Ok(job)
+}
+
+/// All jobs. One left `minting` by a closed app is shown as paused.
+#[tauri::command]
+pub fn list_mint_jobs() -> Result<Vec<MintJob>, String> {
+  let mut jobs = load_mint_jobs()?;
+  if !MINTER_RUNNING.load(Ordering::SeqCst) {
+    for job in jobs.iter_mut().filter(|j| j.status == "minting") {
+      job.status = String::from("paused");
+      job.error = Some(String::from("Interrupted; resume to mint the remaining batches"));
+    }
+  }
+  jobs.sort_by_key(|j| std::cmp::Reverse(j.created));
+  Ok(jobs)
+}
+
+#[tauri::command]
+pub fn get_mint_job(id: String) -> Result<MintJob, String> {
+  find_job(&id)
+}
+
+/// Start or resume a job. The quote (operation `issue_unique`) must cover
+/// the items still pending.
+#[tauri::command]
+pub fn start_mint_job(app_handle: tauri::AppHandle, id: String, quote_id: String) -> Result<MintJob, String> {
+  ensure_config()?;
+  let mut job = find_job(&id)?;
+  if job.status == "completed" {
+    return Err("This job has nothing left to mint".to_string());
+  }
+  if MINTER_RUNNING.swap(true, Ordering::SeqCst) {
+    return Err("Another mint job is running".to_string());
+  }
+  if let Err(e) = take_quote(&quote_id, "issue_unique", &job.root_name, pending_count(&job)) {
+    MINTER_RUNNING.store(false, Ordering::SeqCst);
+    return Err(e);
+  }
+  STOP_REQUESTED.store(false, Ordering::SeqCst);
+  job.status = String::from("minting");
+  job.error = None;
+  if let Err(e) = update_job(&job) {
+    MINTER_RUNNING.store(false, Ordering::SeqCst);
+    return Err(e);
+  }
+  spawn_minter(app_handle, job.clone());
+  Ok(job)
+}
+
+/// Stop after the batch being minted
+#[tauri::command]
+pub fn pause_mint_job() -> Result<(), String> {
+  if !MINTER_RUNNING.load(Ordering::SeqCst) {
+    return Err("No mint job is running".to_string());
+  }
+  STOP_REQUESTED.store(true, Ordering::SeqCst);
+  Ok(())
+}
+
+#[tauri::command]
+pub fn remove_mint_job(id: String) -> Result<(), String> {
+  let job = find_job(&id)?;
+  if job.status == "minting" && MINTER_RUNNING.load(Ordering::SeqCst) {
+    return Err("Pause the job before removing it".to_string());
+  }
+  let mut jobs = load_mint_jobs()?;
+  jobs.retain(|j| j.id != id);
+  save_mint_jobs(&jobs)
+}
+
+/// Write `name,tag,ipfs,address,status,txid,error` for every item
+#[tauri::command]
+pub fn export_mint_report(id: String, path: String) -> Result<usize, String> {
+  let job = find_job(&id)?;
+  let mut csv = String::from("name,tag,ipfs,address,status,txid,error\n");
+  for item in &job.items {
+    csv.push_str(&format!(
+      "{}#{},{},{},{},{},{},\"{}\"\n",
+      job.root_name,
+      item.tag,
+      item.tag,
+      item.ipfs,
+      item.address,
+      item.status,
+      item.txid.clone().unwrap_or_default(),
+      item.error.clone().unwrap_or_default().replace('"', "'")
+    ));
+  }
+  fs::write(&path, csv).map_err(|e| e.to_string())?;
+  Ok(job.items.len())
+}
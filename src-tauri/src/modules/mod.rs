@@ -3,3 +3,28 @@ pub mod utils;
 pub mod files;
 pub mod commands;
 pub mod process;
+pub mod fees;
+pub mod coin_control;
+pub mod coin_selection;
+pub mod consolidation;
+pub mod raw_tx;
+pub mod multisig;
+pub mod watch_only;
+pub mod wallet_dump;
+pub mod seed_backup;
+pub mod rescan;
+pub mod message;
+pub mod backups;
+pub mod safety_backup;
+pub mod wallet_session;
+pub mod wallets;
+pub mod restricted;
+pub mod qualifiers;
+pub mod verifier;
+pub mod rewards;
+pub mod snapshots;
+pub mod messaging;
+pub mod transfers;
+pub mod naming;
+pub mod burns;
+pub mod minting;
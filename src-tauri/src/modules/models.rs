@@ -1,5 +1,5 @@
 use serde::Serialize;
-// use std::collections::HashMap; // Unused
+use std::collections::HashMap;
 
 
 #[derive(Serialize)]
@@ -18,6 +18,8 @@ pub struct WalletInfo {
   pub pending: String,
   pub staked: String,
   pub status: String,
+  pub unlock_remaining: Option<u64>,
+  pub staking_only: bool,
 }
 
 #[derive(Serialize)]
@@ -35,6 +37,9 @@ pub struct DashboardData {
   pub node: NodeInfo,
   pub wallet: WalletInfo,
   pub tx: Vec<TxItem>,
+  pub watch_only: Option<WatchOnlySummary>,
+  pub seed_backup_needed: bool,
+  pub wallets: Vec<WalletBalance>,
 }
 
 #[derive(Serialize)]
@@ -56,6 +61,7 @@ pub struct AddressItem {
   pub label: String,
   pub address: String,
   pub balance: String,
+  pub tags: Vec<String>,
 }
 
 #[derive(Serialize)]
@@ -67,7 +73,7 @@ pub struct AssetItem {
   pub asset_type_label: Option<String>,
 }
 
-#[derive(Serialize, serde::Deserialize)]
+#[derive(Serialize, serde::Deserialize, Clone)]
 pub struct UtxoItem {
   pub txid: String,
   pub vout: u64,
@@ -78,6 +84,12 @@ pub struct UtxoItem {
   pub solvable: Option<bool>,
   pub desc: Option<String>,
   pub safe: Option<bool>,
+  #[serde(rename = "scriptPubKey")]
+  pub script_pub_key: Option<String>,
+  #[serde(default)]
+  pub locked: bool,
+  #[serde(default)]
+  pub frozen: bool,
 }
 
 #[derive(Serialize, serde::Deserialize, Clone)]
@@ -130,9 +142,10 @@ pub struct DataFolderInfo {
   pub config_exists: bool,
   pub wallet_exists: bool,
   pub folder_exists: bool,
+  pub active_wallet: String,
 }
 
-#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(serde::Deserialize, serde::Serialize, Clone, PartialEq)]
 pub struct RawTxInput {
   pub txid: String,
   pub vout: u64,
@@ -145,4 +158,692 @@ pub struct AppSettings {
   pub show_welcome: bool,
   pub hidden_assets: Vec<String>,
   pub asset_order: Vec<String>,
+  pub max_fee: Option<f64>,
+  pub safety_backup_keep: Option<u32>,
+  pub auto_lock_idle_secs: Option<u64>,
+  pub lock_on_minimize: Option<bool>,
+  pub lock_on_leave_wallet: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct FeeEstimate {
+  pub fee_rate: f64,
+  pub source: String,
+  pub conf_target: u32,
+}
+
+#[derive(Serialize, serde::Deserialize)]
+pub struct TxFeePlan {
+  pub size: u64,
+  pub fee_rate: f64,
+  pub fee: String,
+  pub input_total: String,
+  pub output_total: String,
+  pub change: String,
+  pub change_address: Option<String>,
+  pub outputs: HashMap<String, String>,
+}
+
+#[derive(Serialize, serde::Deserialize, Clone)]
+pub struct FrozenCoin {
+  pub txid: String,
+  pub vout: u64,
+  pub note: String,
+  pub date: u64,
+}
+
+#[derive(Serialize)]
+pub struct LockedUtxo {
+  pub txid: String,
+  pub vout: u64,
+  pub note: String,
+  pub frozen: bool,
+  pub locked: bool,
+}
+
+#[derive(Serialize)]
+pub struct CoinSelection {
+  pub strategy: String,
+  pub inputs: Vec<UtxoItem>,
+  pub plan: TxFeePlan,
+  pub note: Option<String>,
+}
+
+#[derive(Serialize, serde::Deserialize, Clone)]
+pub struct ConsolidationBatch {
+  pub inputs: Vec<RawTxInput>,
+  pub input_total: String,
+  pub fee: String,
+  pub output: String,
+  pub size: u64,
+}
+
+#[derive(Serialize, serde::Deserialize, Clone)]
+pub struct ConsolidationPlan {
+  pub destination: String,
+  pub fee_rate: f64,
+  pub batches: Vec<ConsolidationBatch>,
+  pub utxo_count: u64,
+  pub skipped_uneconomical: u64,
+  pub total_fee: String,
+  pub future_savings: String,
+  pub worthwhile: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ConsolidationProgress {
+  pub batch: usize,
+  pub total: usize,
+  pub txid: Option<String>,
+  pub error: Option<String>,
+  pub done: bool,
+}
+
+#[derive(Serialize, serde::Deserialize, Clone)]
+pub struct PrevTxOut {
+  pub txid: String,
+  pub vout: u64,
+  #[serde(rename = "scriptPubKey")]
+  pub script_pub_key: String,
+  #[serde(rename = "redeemScript", skip_serializing_if = "Option::is_none")]
+  pub redeem_script: Option<String>,
+  pub amount: f64,
+}
+
+#[derive(Serialize, serde::Deserialize, Clone)]
+pub struct TxFile {
+  pub version: u32,
+  pub hex: String,
+  pub prevtxs: Vec<PrevTxOut>,
+  pub created: u64,
+  pub signed: bool,
+  pub complete: bool,
+  pub note: String,
+}
+
+#[derive(Serialize)]
+pub struct TxFileResult {
+  pub path: String,
+  pub tx: TxFile,
+  pub plan: Option<TxFeePlan>,
+  pub errors: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct MempoolAcceptResult {
+  pub txid: String,
+  pub allowed: bool,
+  pub reject_reason: Option<String>,
+}
+
+#[derive(Serialize, serde::Deserialize, Clone)]
+pub struct MultisigWallet {
+  pub name: String,
+  pub address: String,
+  pub redeem_script: String,
+  pub required: u32,
+  pub pubkeys: Vec<String>,
+  pub participants: Vec<String>,
+  pub created: u64,
+}
+
+#[derive(Serialize)]
+pub struct MultisigBalance {
+  pub address: String,
+  pub balance: String,
+  pub pending: String,
+  pub utxo_count: u64,
+}
+
+#[derive(Serialize, serde::Deserialize, Clone)]
+pub struct MultisigTx {
+  pub id: String,
+  pub address: String,
+  pub hex: String,
+  pub prevtxs: Vec<PrevTxOut>,
+  pub required: u32,
+  pub signatures: u32,
+  pub signers: Vec<String>,
+  pub complete: bool,
+  pub status: String,
+  pub txid: Option<String>,
+  pub fee: String,
+  pub created: u64,
+}
+
+#[derive(Serialize, serde::Deserialize, Clone)]
+pub struct WatchOnlyEntry {
+  pub label: String,
+  pub address: String,
+  pub pubkey: Option<String>,
+  pub imported: bool,
+  pub date: u64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct AssetBalance {
+  pub name: String,
+  pub balance: String,
+}
+
+#[derive(Serialize)]
+pub struct PortfolioEntry {
+  pub label: String,
+  pub address: String,
+  pub hemp: String,
+  pub assets: Vec<AssetBalance>,
+  pub source: String,
+  pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct Portfolio {
+  pub entries: Vec<PortfolioEntry>,
+  pub total_hemp: String,
+  pub assets: Vec<AssetBalance>,
+}
+
+#[derive(Serialize)]
+pub struct WatchOnlySummary {
+  pub count: u64,
+  pub balance: String,
+  pub assets: Vec<AssetBalance>,
+}
+
+#[derive(Serialize)]
+pub struct WalletDumpResult {
+  pub path: String,
+  pub encrypted: bool,
+  pub keys: u64,
+  pub warning: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct WalletImportProgress {
+  pub stage: String,
+  pub progress: Option<f64>,
+  pub height: Option<u64>,
+  pub done: bool,
+  pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct SeedWords {
+  pub words: Vec<String>,
+  pub passphrase: Option<String>,
+  pub challenge: Vec<usize>,
+  pub fingerprint: String,
+}
+
+#[derive(serde::Deserialize)]
+pub struct SeedWordAnswer {
+  pub position: usize,
+  pub word: String,
+}
+
+#[derive(Serialize)]
+pub struct MasterKeyInfo {
+  pub fingerprint: String,
+  pub root_public: String,
+  pub derivation_path: Option<String>,
+  pub account_public: Option<String>,
+}
+
+#[derive(Serialize, serde::Deserialize, Clone)]
+pub struct SeedBackupRecord {
+  pub wallet_id: String,
+  pub fingerprint: String,
+  pub date: u64,
+}
+
+#[derive(Serialize)]
+pub struct SeedBackupStatus {
+  pub hd: bool,
+  pub backed_up: bool,
+  pub backed_up_at: Option<u64>,
+  pub fingerprint: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct RescanProgress {
+  pub progress: Option<f64>,
+  pub height: Option<u64>,
+  pub start_height: Option<u64>,
+  pub stop_height: Option<u64>,
+  pub done: bool,
+  pub aborted: bool,
+  pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct RescanStatus {
+  pub running: bool,
+  pub progress: Option<f64>,
+  pub height: Option<u64>,
+}
+
+#[derive(Serialize, serde::Deserialize, Clone)]
+pub struct SignedMessage {
+  pub address: String,
+  pub message: String,
+  pub signature: String,
+}
+
+#[derive(Serialize)]
+pub struct MessageVerification {
+  pub address: String,
+  pub message: String,
+  pub signature: String,
+  pub valid: bool,
+  pub error: Option<String>,
+}
+
+#[derive(Serialize, serde::Deserialize, Clone, Default)]
+pub struct BackupConfig {
+  pub enabled: bool,
+  pub destinations: Vec<String>,
+  pub recipient: Option<String>,
+  pub interval_hours: Option<u32>,
+  pub keep_daily: Option<u32>,
+  pub keep_weekly: Option<u32>,
+  pub last_backup: Option<u64>,
+}
+
+#[derive(Serialize, serde::Deserialize, Clone)]
+pub struct BackupManifest {
+  pub version: u32,
+  pub file: String,
+  pub created: u64,
+  pub method: String,
+  pub recipient: Option<String>,
+  pub size: u64,
+  pub sha256: String,
+  pub plain_sha256: String,
+}
+
+#[derive(Serialize)]
+pub struct BackupResult {
+  pub created: u64,
+  pub files: Vec<String>,
+  pub sha256: String,
+  pub rotated: u64,
+  pub errors: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct BackupEntry {
+  pub path: String,
+  pub destination: String,
+  pub created: u64,
+  pub size: u64,
+  pub method: Option<String>,
+  pub has_manifest: bool,
+}
+
+#[derive(Serialize)]
+pub struct BackupVerification {
+  pub path: String,
+  pub checksum_ok: Option<bool>,
+  pub decrypted: bool,
+  pub content_ok: Option<bool>,
+  pub wallet_format_ok: bool,
+  pub errors: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct BackupKey {
+  pub identity: String,
+  pub recipient: String,
+}
+
+#[derive(Serialize)]
+pub struct SafetyBackup {
+  pub name: String,
+  pub path: String,
+  pub reason: String,
+  pub created: u64,
+  pub size: u64,
+}
+
+#[derive(Serialize)]
+pub struct WalletSession {
+  pub unlocked: bool,
+  pub until: Option<u64>,
+  pub remaining: u64,
+  pub staking_only: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct WalletSessionEvent {
+  pub remaining: u64,
+  pub reason: Option<String>,
+}
+
+#[derive(Serialize, serde::Deserialize, Clone)]
+pub struct WalletEntry {
+  pub name: String,
+  pub label: String,
+  pub created: u64,
+}
+
+#[derive(Serialize, serde::Deserialize, Clone, Default)]
+pub struct WalletRegistry {
+  pub active: Option<String>,
+  pub wallets: Vec<WalletEntry>,
+}
+
+#[derive(Serialize)]
+pub struct WalletFileInfo {
+  pub name: String,
+  pub label: String,
+  pub active: bool,
+  pub loaded: bool,
+  pub file_exists: bool,
+}
+
+#[derive(Serialize)]
+pub struct WalletBalance {
+  pub name: String,
+  pub label: String,
+  pub active: bool,
+  pub balance: String,
+  pub pending: String,
+  pub status: String,
+}
+
+#[derive(Serialize)]
+pub struct RestrictedAssetInfo {
+  pub name: String,
+  pub verifier: Option<String>,
+  pub globally_frozen: bool,
+}
+
+#[derive(Serialize)]
+pub struct RestrictedAddress {
+  pub address: String,
+  pub asset_name: String,
+  pub restricted: bool,
+  pub time: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct AddressEligibility {
+  pub address: String,
+  pub asset_name: String,
+  pub frozen: bool,
+  pub globally_frozen: bool,
+  pub verifier: Option<String>,
+  pub verifier_ok: Option<bool>,
+  pub eligible: bool,
+  pub reasons: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct TagResult {
+  pub address: String,
+  pub txid: Option<String>,
+  pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct TagBatchResult {
+  pub tag: String,
+  pub results: Vec<TagResult>,
+  pub succeeded: u32,
+  pub failed: u32,
+}
+
+#[derive(Serialize)]
+pub struct TaggedAddress {
+  pub address: String,
+  pub tag: String,
+  pub assigned: bool,
+  pub time: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct VerifierCheck {
+  pub verifier: String,
+  pub valid: bool,
+  pub normalized: Option<String>,
+  pub stripped: Option<String>,
+  pub qualifiers: Vec<String>,
+  pub error: Option<String>,
+  pub node_valid: Option<bool>,
+  pub node_error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct VerifierEvaluation {
+  pub address: String,
+  pub verifier: String,
+  pub tags: Vec<String>,
+  pub satisfied: bool,
+  pub missing: Vec<String>,
+  pub forbidden: Vec<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct SnapshotHolder {
+  pub address: String,
+  pub amount: String,
+}
+
+#[derive(Serialize)]
+pub struct SnapshotRequest {
+  pub asset_name: String,
+  pub block_height: u64,
+}
+
+#[derive(Serialize, serde::Deserialize, Clone)]
+pub struct RewardPlan {
+  pub id: String,
+  pub asset_name: String,
+  pub snapshot_height: u64,
+  pub distribution_asset: String,
+  pub gross_amount: String,
+  pub exception_addresses: Vec<String>,
+  pub change_address: Option<String>,
+  pub status: String,
+  pub created: u64,
+  pub node_status: Option<String>,
+  pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct RewardPayout {
+  pub address: String,
+  pub holding: String,
+  pub amount: String,
+}
+
+#[derive(Serialize)]
+pub struct RewardPreview {
+  pub plan_id: String,
+  pub asset_name: String,
+  pub snapshot_height: u64,
+  pub distribution_asset: String,
+  pub gross_amount: String,
+  pub distributed: String,
+  pub remainder: String,
+  pub holders: usize,
+  pub recipients: usize,
+  pub excluded: Vec<String>,
+  pub payouts: Vec<RewardPayout>,
+  pub node_result: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct HolderShare {
+  pub rank: usize,
+  pub address: String,
+  pub amount: String,
+  pub percent: f64,
+}
+
+#[derive(Serialize)]
+pub struct SnapshotAnalytics {
+  pub asset_name: String,
+  pub height: u64,
+  pub holders: usize,
+  pub total: String,
+  pub supply: Option<String>,
+  pub top: Vec<HolderShare>,
+  pub top1_percent: f64,
+  pub top10_percent: f64,
+  pub gini: f64,
+}
+
+#[derive(Serialize, serde::Deserialize, Clone)]
+pub struct HolderHistoryPoint {
+  pub asset_name: String,
+  pub height: u64,
+  pub holders: usize,
+  pub total: String,
+  pub gini: f64,
+  pub recorded: u64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct AssetMessage {
+  pub id: String,
+  pub asset_name: String,
+  pub ipfs_hash: String,
+  pub time: String,
+  pub block_height: u64,
+  pub status: String,
+  pub expire_time: Option<String>,
+  pub is_new: bool,
+}
+
+#[derive(serde::Deserialize)]
+pub struct KeyImportItem {
+  pub priv_key: String,
+  pub label: String,
+}
+
+#[derive(Serialize)]
+pub struct KeyImportResult {
+  pub imported: u32,
+  pub failed: u32,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct TransferRecipient {
+  pub address: String,
+  pub amount: String,
+}
+
+#[derive(Serialize)]
+pub struct TransferResult {
+  pub address: String,
+  pub amount: String,
+  pub txid: Option<String>,
+  pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct BatchTransferResult {
+  pub asset: String,
+  pub total: String,
+  pub sent: u32,
+  pub failed: u32,
+  pub results: Vec<TransferResult>,
+}
+
+#[derive(Serialize)]
+pub struct AssetNameCheck {
+  pub name: String,
+  pub valid: bool,
+  pub kind: Option<String>,
+  pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct IpfsHashCheck {
+  pub hash: String,
+  pub valid: bool,
+  pub kind: Option<String>,
+  pub onchain: Option<String>,
+  pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct IssuanceQuote {
+  pub quote_id: String,
+  pub operation: String,
+  pub name: String,
+  pub kind: String,
+  pub count: u32,
+  pub burn: Option<String>,
+  pub burn_source: String,
+  pub network_fee: String,
+  pub fee_rate: f64,
+  pub balance: String,
+  pub resulting_balance: Option<String>,
+  pub sufficient: bool,
+  pub expires: u64,
+}
+
+#[derive(Serialize)]
+pub struct BurnRecord {
+  pub txid: String,
+  pub time: String,
+  pub kind: String,
+  pub address: String,
+  pub amount: String,
+  pub fee: String,
+  pub confirmations: i64,
+}
+
+#[derive(Serialize)]
+pub struct BurnKindTotal {
+  pub kind: String,
+  pub count: u32,
+  pub total: String,
+}
+
+#[derive(Serialize)]
+pub struct BurnReport {
+  pub total: String,
+  pub fees: String,
+  pub transactions: u32,
+  pub by_kind: Vec<BurnKindTotal>,
+  pub records: Vec<BurnRecord>,
+}
+
+#[derive(Serialize, serde::Deserialize, Clone)]
+pub struct MintItem {
+  pub tag: String,
+  pub ipfs: String,
+  pub address: String,
+  pub batch: u32,
+  pub status: String,
+  pub txid: Option<String>,
+  pub error: Option<String>,
+}
+
+#[derive(Serialize, serde::Deserialize, Clone)]
+pub struct MintJob {
+  pub id: String,
+  pub root_name: String,
+  pub source: String,
+  pub batch_size: u32,
+  pub batches: u32,
+  pub status: String,
+  pub created: u64,
+  pub items: Vec<MintItem>,
+  pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct MintProgress {
+  pub job_id: String,
+  pub status: String,
+  pub minted: u32,
+  pub total: u32,
+  pub batch: u32,
+  pub batches: u32,
+  pub txid: Option<String>,
+  pub error: Option<String>,
 }
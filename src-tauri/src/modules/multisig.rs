@@ -0,0 +1,419 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::Local;
+use uuid::Uuid;
+
+// Import local modules
+use crate::modules::models::{MultisigBalance, MultisigTx, MultisigWallet, PrevTxOut, RawTxInput, TxFile};
+use crate::modules::utils::{to_sats, format_sats, COIN};
+use crate::modules::commands::run_cli;
+use crate::modules::files::{data_dir, ensure_config};
+use crate::modules::fees::{resolve_fee_rate, plan_fee_sized, multisig_input_size};
+use crate::modules::rescan::rescan_after_import;
+use crate::modules::raw_tx::{sign_hex, read_tx_file, write_tx_file, broadcast_signed_transaction, TX_FILE_VERSION};
+
+// Standard P2SH multisig allows at most 15 keys
+pub const MAX_MULTISIG_KEYS: usize = 15;
+
+fn multisig_wallets_path() -> Result<PathBuf, String> {
+  Ok(data_dir()?.join("multisig_wallets.json"))
+}
+
+fn multisig_txs_path() -> Result<PathBuf, String> {
+  Ok(data_dir()?.join("multisig_txs.json"))
+}
+
+pub fn load_multisig_wallets() -> Result<Vec<MultisigWallet>, String> {
+  let path = multisig_wallets_path()?;
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+  let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+  let wallets: Vec<MultisigWallet> = serde_json::from_str(&content).unwrap_or_default();
+  Ok(wallets)
+}
+
+fn save_multisig_wallets(wallets: &[MultisigWallet]) -> Result<(), String> {
+  let path = multisig_wallets_path()?;
+  let content = serde_json::to_string_pretty(wallets).map_err(|e| e.to_string())?;
+  fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+fn load_multisig_txs() -> Result<Vec<MultisigTx>, String> {
+  let path = multisig_txs_path()?;
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+  let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+  let txs: Vec<MultisigTx> = serde_json::from_str(&content).unwrap_or_default();
+  Ok(txs)
+}
+
+fn save_multisig_txs(txs: &[MultisigTx]) -> Result<(), String> {
+  let path = multisig_txs_path()?;
+  let content = serde_json::to_string_pretty(txs).map_err(|e| e.to_string())?;
+  fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+fn find_wallet(address: &str) -> Result<MultisigWallet, String> {
+  load_multisig_wallets()?
+    .into_iter()
+    .find(|w| w.address == address)
+    .ok_or_else(|| format!("Unknown multisig address {}", address))
+}
+
+fn update_tx(tx: &MultisigTx) -> Result<(), String> {
+  let mut txs = load_multisig_txs()?;
+  match txs.iter_mut().find(|t| t.id == tx.id) {
+    Some(existing) => *existing = tx.clone(),
+    None => txs.push(tx.clone()),
+  }
+  save_multisig_txs(&txs)
+}
+
+fn find_tx(id: &str) -> Result<MultisigTx, String> {
+  load_multisig_txs()?
+    .into_iter()
+    .find(|t| t.id == id)
+    .ok_or_else(|| format!("Unknown multisig transaction {}", id))
+}
+
+/// Accept either hex pubkeys or wallet addresses; addresses are resolved
+/// to their pubkey through `validateaddress`
+fn resolve_pubkey(key: &str) -> Result<String, String> {
+  let key = key.trim();
+  let is_hex = key.chars().all(|c| c.is_ascii_hexdigit());
+  if is_hex && (key.len() == 66 || key.len() == 130) {
+    return Ok(key.to_string());
+  }
+  let raw = run_cli(&[String::from("validateaddress"), key.to_string()])?;
+  let info: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  if !info["isvalid"].as_bool().unwrap_or(false) {
+    return Err(format!("Not a valid pubkey or address: {}", key));
+  }
+  info["pubkey"]
+    .as_str()
+    .map(|s| s.to_string())
+    .ok_or_else(|| format!("Pubkey for {} is unknown; ask the participant for the hex pubkey", key))
+}
+
+/// Smallest number of signatures present on any input
+fn count_signatures(hex: &str) -> Result<u32, String> {
+  let raw = run_cli(&[String::from("decoderawtransaction"), hex.to_string()])?;
+  let value: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  let vins = value["vin"].as_array().ok_or("Transaction has no inputs")?;
+  let count = vins
+    .iter()
+    .map(|vin| {
+      vin["scriptSig"]["asm"]
+        .as_str()
+        .unwrap_or("")
+        .split_whitespace()
+        .filter(|t| t.ends_with(']'))
+        .count() as u32
+    })
+    .min()
+    .unwrap_or(0);
+  Ok(count)
+}
+
+/// Unspent outputs of the multisig address, from the wallet if it watches
+/// the address, otherwise from the address index
+fn multisig_utxos(address: &str) -> Result<Vec<(PrevTxOut, u64)>, String> {
+  let filter = serde_json::to_string(&[address]).map_err(|e| e.to_string())?;
+  let raw = run_cli(&[
+    String::from("listunspent"),
+    String::from("0"),
+    String::from("9999999"),
+    filter,
+  ])?;
+  let listed: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  let mut utxos = Vec::new();
+  for u in listed.as_array().unwrap_or(&Vec::new()) {
+    utxos.push((
+      PrevTxOut {
+        txid: u["txid"].as_str().unwrap_or("").to_string(),
+        vout: u["vout"].as_u64().unwrap_or(0),
+        script_pub_key: u["scriptPubKey"].as_str().unwrap_or("").to_string(),
+        redeem_script: None,
+        amount: u["amount"].as_f64().unwrap_or(0.0),
+      },
+      u["confirmations"].as_u64().unwrap_or(0),
+    ));
+  }
+  if !utxos.is_empty() {
+    return Ok(utxos);
+  }
+
+  let query = serde_json::json!({ "addresses": [address] }).to_string();
+  if let Ok(raw) = run_cli(&[String::from("getaddressutxos"), query]) {
+    let indexed: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+    for u in indexed.as_array().unwrap_or(&Vec::new()) {
+      // Asset outputs are listed here too; only plain HEMP can be spent by this flow
+      if u["assetName"].as_str().map(|a| a != "HEMP").unwrap_or(false) {
+        continue;
+      }
+      let sats = u["satoshis"].as_u64().unwrap_or(0);
+      utxos.push((
+        PrevTxOut {
+          txid: u["txid"].as_str().unwrap_or("").to_string(),
+          vout: u["outputIndex"].as_u64().unwrap_or(0),
+          script_pub_key: u["script"].as_str().unwrap_or("").to_string(),
+          redeem_script: None,
+          amount: sats as f64 / COIN as f64,
+        },
+        1,
+      ));
+    }
+  }
+  Ok(utxos)
+}
+
+#[tauri::command]
+pub fn create_multisig_address(
+  app_handle: tauri::AppHandle,
+  name: String,
+  required: u32,
+  keys: Vec<String>,
+  participants: Vec<String>,
+  rescan: bool,
+  rescan_from: Option<u64>,
+) -> Result<MultisigWallet, String> {
+  ensure_config()?;
+  if keys.is_empty() || keys.len() > MAX_MULTISIG_KEYS {
+    return Err(format!("Between 1 and {} keys are required", MAX_MULTISIG_KEYS));
+  }
+  if required == 0 || required as usize > keys.len() {
+    return Err("Required signatures must be between 1 and the number of keys".to_string());
+  }
+
+  let pubkeys = keys.iter().map(|k| resolve_pubkey(k)).collect::<Result<Vec<String>, String>>()?;
+  let keys_json = serde_json::to_string(&pubkeys).map_err(|e| e.to_string())?;
+
+  let raw = run_cli(&[String::from("createmultisig"), required.to_string(), keys_json.clone()])?;
+  let created: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  let address = created["address"].as_str().ok_or("createmultisig returned no address")?.to_string();
+  let redeem_script = created["redeemScript"].as_str().ok_or("createmultisig returned no redeemScript")?.to_string();
+
+  // Store the script in the wallet so it can add its own signature, and
+  // watch the address so its coins show up in listunspent
+  run_cli(&[String::from("addmultisigaddress"), required.to_string(), keys_json, name.clone()])?;
+  if run_cli(&[
+    String::from("importaddress"),
+    address.clone(),
+    name.clone(),
+    String::from("false"),
+  ])
+  .is_ok()
+  {
+    rescan_after_import(app_handle, rescan, rescan_from);
+  }
+
+  let wallet = MultisigWallet {
+    name,
+    address: address.clone(),
+    redeem_script,
+    required,
+    pubkeys,
+    participants,
+    created: Local::now().timestamp() as u64,
+  };
+  let mut wallets = load_multisig_wallets()?;
+  wallets.retain(|w| w.address != address);
+  wallets.push(wallet.clone());
+  save_multisig_wallets(&wallets)?;
+  Ok(wallet)
+}
+
+#[tauri::command]
+pub fn list_multisig_wallets() -> Result<Vec<MultisigWallet>, String> {
+  load_multisig_wallets()
+}
+
+#[tauri::command]
+pub fn remove_multisig_wallet(address: String) -> Result<(), String> {
+  let mut wallets = load_multisig_wallets()?;
+  wallets.retain(|w| w.address != address);
+  save_multisig_wallets(&wallets)
+}
+
+#[tauri::command]
+pub fn get_multisig_balance(address: String) -> Result<MultisigBalance, String> {
+  ensure_config()?;
+  let utxos = multisig_utxos(&address)?;
+  let mut confirmed = 0u64;
+  let mut pending = 0u64;
+  for (u, confs) in &utxos {
+    if *confs > 0 {
+      confirmed += to_sats(u.amount);
+    } else {
+      pending += to_sats(u.amount);
+    }
+  }
+  Ok(MultisigBalance {
+    address,
+    balance: format_sats(confirmed),
+    pending: format_sats(pending),
+    utxo_count: utxos.len() as u64,
+  })
+}
+
+/// Build a spend from the multisig address using all of its coins; change
+/// goes back to the multisig address. The result is tracked until every
+/// required co-signer has signed.
+#[tauri::command]
+pub fn create_multisig_spend(
+  address: String,
+  outputs: HashMap<String, String>,
+  fee_rate: Option<f64>,
+  conf_target: Option<u32>,
+) -> Result<MultisigTx, String> {
+  ensure_config()?;
+  let wallet = find_wallet(&address)?;
+  if outputs.is_empty() {
+    return Err("At least one output is required".to_string());
+  }
+  let utxos = multisig_utxos(&address)?;
+  if utxos.is_empty() {
+    return Err("Multisig address has no spendable coins".to_string());
+  }
+
+  let estimate = resolve_fee_rate(fee_rate, conf_target)?;
+  let input_total: u64 = utxos.iter().map(|(u, _)| to_sats(u.amount)).sum();
+  let input_size = utxos.len() as u64 * multisig_input_size(wallet.required, wallet.pubkeys.len() as u32);
+  let plan = plan_fee_sized(input_total, input_size, &outputs, estimate.fee_rate, Some(address.clone()))?;
+
+  let inputs: Vec<RawTxInput> = utxos
+    .iter()
+    .map(|(u, _)| RawTxInput { txid: u.txid.clone(), vout: u.vout })
+    .collect();
+  let inputs_json = serde_json::to_string(&inputs).map_err(|e| e.to_string())?;
+  let outputs_json = serde_json::to_string(&plan.outputs).map_err(|e| e.to_string())?;
+  let hex = run_cli(&[String::from("createrawtransaction"), inputs_json, outputs_json])?;
+
+  let prevtxs = utxos
+    .into_iter()
+    .map(|(mut u, _)| {
+      u.redeem_script = Some(wallet.redeem_script.clone());
+      u
+    })
+    .collect();
+
+  let tx = MultisigTx {
+    id: Uuid::new_v4().to_string(),
+    address,
+    hex,
+    prevtxs,
+    required: wallet.required,
+    signatures: 0,
+    signers: Vec::new(),
+    complete: false,
+    status: "pending".to_string(),
+    txid: None,
+    fee: plan.fee,
+    created: Local::now().timestamp() as u64,
+  };
+  update_tx(&tx)?;
+  Ok(tx)
+}
+
+#[tauri::command]
+pub fn list_multisig_txs(address: Option<String>) -> Result<Vec<MultisigTx>, String> {
+  let txs = load_multisig_txs()?;
+  Ok(match address {
+    Some(a) => txs.into_iter().filter(|t| t.address == a).collect(),
+    None => txs,
+  })
+}
+
+/// Add this wallet's signature to a pending multisig transaction
+#[tauri::command]
+pub fn sign_multisig_tx(id: String, signer: Option<String>) -> Result<MultisigTx, String> {
+  ensure_config()?;
+  let mut tx = find_tx(&id)?;
+  if tx.status == "broadcast" {
+    return Err("Transaction was already broadcast".to_string());
+  }
+  let (hex, complete, _) = sign_hex(&tx.hex, &tx.prevtxs)?;
+  let signatures = count_signatures(&hex)?;
+  if signatures <= tx.signatures && !complete {
+    return Err("This wallet holds none of the missing keys".to_string());
+  }
+  tx.hex = hex;
+  tx.signatures = signatures;
+  tx.complete = complete;
+  tx.status = if complete { "complete" } else { "pending" }.to_string();
+  if let Some(s) = signer.filter(|s| !s.trim().is_empty()) {
+    tx.signers.push(s);
+  }
+  update_tx(&tx)?;
+  Ok(tx)
+}
+
+/// Merge partially signed copies from other co-signers via `combinerawtransaction`
+#[tauri::command]
+pub fn combine_multisig_tx(id: String, hexes: Vec<String>) -> Result<MultisigTx, String> {
+  ensure_config()?;
+  let mut tx = find_tx(&id)?;
+  let mut all = vec![tx.hex.clone()];
+  all.extend(hexes.into_iter().map(|h| h.trim().to_string()).filter(|h| !h.is_empty()));
+  let json = serde_json::to_string(&all).map_err(|e| e.to_string())?;
+  let combined = run_cli(&[String::from("combinerawtransaction"), json])?;
+
+  // A no-key sign pass reports whether the combined tx is complete
+  let (hex, complete, _) = sign_hex(&combined, &tx.prevtxs)?;
+  tx.signatures = count_signatures(&hex)?;
+  tx.hex = hex;
+  tx.complete = complete;
+  tx.status = if complete { "complete" } else { "pending" }.to_string();
+  update_tx(&tx)?;
+  Ok(tx)
+}
+
+/// Write the pending transaction as a transaction file a co-signer can
+/// sign with `sign_transaction_file` on their own commander
+#[tauri::command]
+pub fn export_multisig_tx(id: String, path: String) -> Result<(), String> {
+  let tx = find_tx(&id)?;
+  let file = TxFile {
+    version: TX_FILE_VERSION,
+    hex: tx.hex,
+    prevtxs: tx.prevtxs,
+    created: tx.created,
+    signed: tx.signatures > 0,
+    complete: tx.complete,
+    note: format!("multisig {} ({} of {} signatures)", tx.address, tx.signatures, tx.required),
+  };
+  write_tx_file(Path::new(&path), &file)
+}
+
+#[tauri::command]
+pub fn import_multisig_signatures(id: String, path: String) -> Result<MultisigTx, String> {
+  let file = read_tx_file(Path::new(&path))?;
+  combine_multisig_tx(id, vec![file.hex])
+}
+
+#[tauri::command]
+pub fn broadcast_multisig_tx(id: String) -> Result<String, String> {
+  ensure_config()?;
+  let mut tx = find_tx(&id)?;
+  if !tx.complete {
+    return Err(format!(
+      "Transaction needs {} signatures, has {}",
+      tx.required, tx.signatures
+    ));
+  }
+  let txid = broadcast_signed_transaction(tx.hex.clone())?;
+  tx.txid = Some(txid.clone());
+  tx.status = "broadcast".to_string();
+  update_tx(&tx)?;
+  Ok(txid)
+}
+
+#[tauri::command]
+pub fn discard_multisig_tx(id: String) -> Result<(), String> {
+  let mut txs = load_multisig_txs()?;
+  txs.retain(|t| t.id != id);
+  save_multisig_txs(&txs)
+}
@@ -0,0 +1,410 @@
+// Import local modules
+use crate::modules::models::{AssetNameCheck, IpfsHashCheck};
+
+// Local copies of the node's asset naming and IPFS hash rules, so a typo
+// is caught before it costs a failed RPC or a burned issuance fee.
+//
+//   ROOT            root asset           ROOT/SUB   sub asset
+//   ROOT#tag        unique asset (NFT)   ROOT~chan  message channel
+//   ROOT!           owner token          $ROOT      restricted asset
+//   #QUAL           qualifier            #QUAL/#SUB sub-qualifier
+
+/// Longest full name, parents and the owner `!` included
+pub const MAX_NAME_LENGTH: usize = 32;
+pub const MIN_ROOT_LENGTH: usize = 3;
+pub const MAX_CHANNEL_LENGTH: usize = 12;
+
+/// Names the chain keeps for its own coin
+const RESERVED_NAMES: &[&str] = &["HEMP", "HEMP0X", "HEMPCOIN"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AssetKind {
+  Root,
+  Sub,
+  Unique,
+  Channel,
+  Owner,
+  Restricted,
+  Qualifier,
+  SubQualifier,
+}
+
+impl AssetKind {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      AssetKind::Root => "root",
+      AssetKind::Sub => "sub",
+      AssetKind::Unique => "unique",
+      AssetKind::Channel => "channel",
+      AssetKind::Owner => "owner",
+      AssetKind::Restricted => "restricted",
+      AssetKind::Qualifier => "qualifier",
+      AssetKind::SubQualifier => "sub_qualifier",
+    }
+  }
+
+  fn describe(&self) -> &'static str {
+    match self {
+      AssetKind::Root => "a root asset",
+      AssetKind::Sub => "a sub asset",
+      AssetKind::Unique => "a unique asset",
+      AssetKind::Channel => "a message channel",
+      AssetKind::Owner => "an owner token",
+      AssetKind::Restricted => "a restricted asset",
+      AssetKind::Qualifier => "a qualifier",
+      AssetKind::SubQualifier => "a sub-qualifier",
+    }
+  }
+}
+
+fn is_segment_char(c: char) -> bool {
+  c.is_ascii_uppercase() || c.is_ascii_digit() || c == '.' || c == '_'
+}
+
+fn is_punct(c: char) -> bool {
+  c == '.' || c == '_'
+}
+
+/// One `/`-separated part of a root, sub or qualifier name
+fn check_segment(segment: &str, min_len: usize, what: &str) -> Result<(), String> {
+  if segment.is_empty() {
+    return Err(format!("The {} name is empty", what));
+  }
+  if let Some(c) = segment.chars().find(|c| !is_segment_char(*c)) {
+    if c.is_ascii_lowercase() {
+      return Err(format!(
+        "Asset names are upper case: use '{}' instead of '{}'",
+        segment.to_ascii_uppercase(),
+        segment
+      ));
+    }
+    return Err(format!(
+      "'{}' is not allowed in the {} name '{}'; only A-Z, 0-9, '.' and '_' are",
+      c, what, segment
+    ));
+  }
+  if segment.len() < min_len {
+    return Err(format!(
+      "The {} name '{}' is too short; it needs at least {} characters",
+      what, segment, min_len
+    ));
+  }
+  if segment.starts_with(is_punct) || segment.ends_with(is_punct) {
+    return Err(format!("The {} name '{}' cannot start or end with '.' or '_'", what, segment));
+  }
+  if segment.chars().zip(segment.chars().skip(1)).any(|(a, b)| is_punct(a) && is_punct(b)) {
+    return Err(format!("The {} name '{}' cannot have '.' or '_' next to each other", what, segment));
+  }
+  if min_len == MIN_ROOT_LENGTH && RESERVED_NAMES.contains(&segment) {
+    return Err(format!("'{}' is reserved for the chain's own coin", segment));
+  }
+  Ok(())
+}
+
+/// `ROOT` or `ROOT/SUB/...`
+fn check_parent(name: &str) -> Result<AssetKind, String> {
+  let mut parts = name.split('/');
+  check_segment(parts.next().unwrap_or(""), MIN_ROOT_LENGTH, "root")?;
+  let mut kind = AssetKind::Root;
+  for part in parts {
+    check_segment(part, 1, "sub asset")?;
+    kind = AssetKind::Sub;
+  }
+  Ok(kind)
+}
+
+fn check_unique_tag(tag: &str) -> Result<(), String> {
+  if tag.is_empty() {
+    return Err("The unique tag after '#' is empty".to_string());
+  }
+  let allowed = |c: char| c.is_ascii_alphanumeric() || "-@$%&*()[]{}_.?:".contains(c);
+  match tag.chars().find(|c| !allowed(*c)) {
+    Some(c) => Err(format!(
+      "'{}' is not allowed in the unique tag '{}'; use letters, digits or -@$%&*()[]{{}}_.?:",
+      c, tag
+    )),
+    None => Ok(()),
+  }
+}
+
+fn check_channel(channel: &str) -> Result<(), String> {
+  if channel.is_empty() {
+    return Err("The channel name after '~' is empty".to_string());
+  }
+  if let Some(c) = channel.chars().find(|c| !(c.is_ascii_alphanumeric() || *c == '_')) {
+    return Err(format!("'{}' is not allowed in the channel name '{}'; use letters, digits or '_'", c, channel));
+  }
+  if channel.len() > MAX_CHANNEL_LENGTH {
+    return Err(format!(
+      "The channel name '{}' is {} characters; the limit is {}",
+      channel,
+      channel.len(),
+      MAX_CHANNEL_LENGTH
+    ));
+  }
+  Ok(())
+}
+
+/// `#QUAL` or `#QUAL/#SUB`
+fn check_qualifier(name: &str) -> Result<AssetKind, String> {
+  let parts: Vec<&str> = name.split('/').collect();
+  if parts.len() > 2 {
+    return Err("Qualifiers can only have one sub-qualifier level (#QUAL/#SUB)".to_string());
+  }
+  let root = parts[0].trim_start_matches('#');
+  if RESERVED_NAMES.contains(&root) {
+    return Err(format!("'#{}' is reserved for the chain's own coin", root));
+  }
+  check_segment(root, MIN_ROOT_LENGTH, "qualifier")?;
+  match parts.get(1) {
+    None => Ok(AssetKind::Qualifier),
+    Some(sub) => match sub.strip_prefix('#') {
+      Some(sub) => check_segment(sub, 1, "sub-qualifier").map(|_| AssetKind::SubQualifier),
+      None => Err(format!("Sub-qualifiers start with '#': use '{}/#{}'", parts[0], sub)),
+    },
+  }
+}
+
+/// Kind of a full asset name, or why the node would reject it
+pub fn check_asset_name(name: &str) -> Result<AssetKind, String> {
+  if name.is_empty() {
+    return Err("Asset name is empty".to_string());
+  }
+  if name.trim() != name || name.contains(char::is_whitespace) {
+    return Err("Asset names cannot contain spaces".to_string());
+  }
+  if name.len() > MAX_NAME_LENGTH {
+    return Err(format!(
+      "'{}' is {} characters; the limit is {} including parent names",
+      name,
+      name.len(),
+      MAX_NAME_LENGTH
+    ));
+  }
+
+  if let Some(root) = name.strip_prefix('$') {
+    if root.contains('/') {
+      return Err("Restricted assets can only be root names ($NAME), not sub assets".to_string());
+    }
+    return check_segment(root, MIN_ROOT_LENGTH, "restricted").map(|_| AssetKind::Restricted);
+  }
+  if name.starts_with('#') {
+    return check_qualifier(name);
+  }
+  if let Some(parent) = name.strip_suffix('!') {
+    return match check_parent(parent) {
+      Ok(_) => Ok(AssetKind::Owner),
+      Err(_) if parent.contains(['#', '~', '!']) => {
+        Err("Only root and sub assets have owner tokens".to_string())
+      }
+      Err(e) => Err(e),
+    };
+  }
+  if let Some((parent, channel)) = name.split_once('~') {
+    check_parent(parent)?;
+    return check_channel(channel).map(|_| AssetKind::Channel);
+  }
+  if let Some((parent, tag)) = name.split_once('#') {
+    check_parent(parent)?;
+    return check_unique_tag(tag).map(|_| AssetKind::Unique);
+  }
+  if name.contains(['!', '$']) {
+    return Err("'!' may only end an owner token and '$' only start a restricted asset".to_string());
+  }
+  let kind = check_parent(name)?;
+  // Issued names leave room for the `!` of their owner token
+  if name.len() > MAX_NAME_LENGTH - 1 {
+    return Err(format!(
+      "'{}' is {} characters; the limit is {} so its owner token '{}!' still fits",
+      name,
+      name.len(),
+      MAX_NAME_LENGTH - 1,
+      name
+    ));
+  }
+  Ok(kind)
+}
+
+/// `check_asset_name`, failing unless the kind is one `action` accepts
+pub fn expect_asset_kind(name: &str, allowed: &[AssetKind], action: &str) -> Result<AssetKind, String> {
+  let kind = check_asset_name(name)?;
+  if allowed.contains(&kind) {
+    Ok(kind)
+  } else {
+    Err(format!("'{}' is {}, which cannot be {}", name, kind.describe(), action))
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HashKind {
+  CidV0,
+  CidV1,
+  Txid,
+}
+
+impl HashKind {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      HashKind::CidV0 => "cidv0",
+      HashKind::CidV1 => "cidv1",
+      HashKind::Txid => "txid",
+    }
+  }
+}
+
+/// Multihash prefix of a 32-byte sha2-256 digest, all a CIDv0 holds
+const SHA256_MULTIHASH: [u8; 2] = [0x12, 0x20];
+const DAG_PB_CODEC: u64 = 0x70;
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Option<u64> {
+  let mut value = 0u64;
+  for shift in (0..63).step_by(7) {
+    let byte = *bytes.get(*pos)?;
+    *pos += 1;
+    value |= ((byte & 0x7f) as u64) << shift;
+    if byte & 0x80 == 0 {
+      return Some(value);
+    }
+  }
+  None
+}
+
+/// RFC 4648 base32, lower case and unpadded as multibase `b` uses it
+fn decode_base32(input: &str) -> Option<Vec<u8>> {
+  let mut out = Vec::new();
+  let (mut buffer, mut bits) = (0u32, 0);
+  for c in input.chars() {
+    let value = "abcdefghijklmnopqrstuvwxyz234567".find(c)? as u32;
+    buffer = (buffer << 5) | value;
+    bits += 5;
+    if bits >= 8 {
+      bits -= 8;
+      out.push((buffer >> bits) as u8);
+      buffer &= (1 << bits) - 1;
+    }
+  }
+  Some(out)
+}
+
+fn decode_hex(input: &str) -> Option<Vec<u8>> {
+  input
+    .as_bytes()
+    .chunks(2)
+    .map(|pair| {
+      let pair = std::str::from_utf8(pair).ok().filter(|p| p.len() == 2)?;
+      u8::from_str_radix(pair, 16).ok()
+    })
+    .collect()
+}
+
+fn check_cid_v0(hash: &str) -> Result<String, String> {
+  let bytes = bs58::decode(hash)
+    .into_vec()
+    .map_err(|_| format!("'{}' is not valid base58; CIDv0 hashes cannot contain 0, O, I or l", hash))?;
+  if bytes.len() != 34 || bytes[..2] != SHA256_MULTIHASH {
+    return Err("CIDv0 hashes are 46 characters starting with Qm (a sha2-256 multihash)".to_string());
+  }
+  Ok(hash.to_string())
+}
+
+/// A CIDv1 can only go on chain when it is the CIDv0 of a dag-pb sha2-256
+/// block in another encoding; it is then converted to that CIDv0
+fn check_cid_v1(hash: &str) -> Result<String, String> {
+  let base = hash.chars().next().unwrap_or_default();
+  let body = &hash[base.len_utf8()..];
+  let bytes = match base {
+    'b' => decode_base32(body),
+    'z' => bs58::decode(body).into_vec().ok(),
+    'f' => decode_hex(body),
+    _ => None,
+  }
+  .ok_or_else(|| {
+    format!(
+      "'{}' is not an IPFS hash: CIDv0 starts with Qm, CIDv1 with b (base32), z (base58) or f (hex)",
+      hash
+    )
+  })?;
+
+  let mut pos = 0;
+  let version = read_varint(&bytes, &mut pos);
+  let codec = read_varint(&bytes, &mut pos);
+  let hash_code = read_varint(&bytes, &mut pos);
+  let digest_len = read_varint(&bytes, &mut pos);
+  match (version, codec, hash_code, digest_len) {
+    (Some(1), Some(codec), Some(0x12), Some(32)) if bytes.len() - pos == 32 => {
+      if codec != DAG_PB_CODEC {
+        return Err(format!(
+          "This CIDv1 uses codec 0x{:x}; only dag-pb (0x70) content, the kind `ipfs add` makes by default, fits on chain",
+          codec
+        ));
+      }
+      let mut multihash = SHA256_MULTIHASH.to_vec();
+      multihash.extend_from_slice(&bytes[pos..]);
+      Ok(bs58::encode(multihash).into_string())
+    }
+    (Some(1), Some(_), Some(code), Some(len)) if bytes.len() - pos == len as usize => Err(format!(
+      "This CIDv1 uses hash function 0x{:x}; the chain only stores sha2-256 hashes",
+      code
+    )),
+    _ => Err(format!("'{}' is not a well-formed CIDv1", hash)),
+  }
+}
+
+/// Kind of an IPFS hash or txid, and the form the node stores
+pub fn check_ipfs_hash(hash: &str) -> Result<(HashKind, String), String> {
+  let hash = hash.trim();
+  if hash.is_empty() {
+    return Err("IPFS hash is empty".to_string());
+  }
+  if let Some(c) = hash.chars().find(|c| !c.is_ascii_alphanumeric()) {
+    return Err(format!("'{}' is not allowed in an IPFS hash or txid; they are letters and digits only", c));
+  }
+  if hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+    return Ok((HashKind::Txid, hash.to_ascii_lowercase()));
+  }
+  if hash.starts_with("Qm") {
+    if hash.len() != 46 {
+      return Err(format!("CIDv0 hashes are 46 characters; '{}' has {}", hash, hash.len()));
+    }
+    return check_cid_v0(hash).map(|h| (HashKind::CidV0, h));
+  }
+  if hash.len() == 64 || (hash.len() > 40 && hash.chars().all(|c| c.is_ascii_hexdigit())) {
+    return Err("A txid is 64 hexadecimal characters (32 bytes)".to_string());
+  }
+  check_cid_v1(hash).map(|h| (HashKind::CidV1, h))
+}
+
+/// On-chain form of an optional hash: empty stays empty
+pub fn onchain_ipfs_hash(hash: &str) -> Result<String, String> {
+  if hash.trim().is_empty() {
+    return Ok(String::new());
+  }
+  check_ipfs_hash(hash).map(|(_, onchain)| onchain)
+}
+
+#[tauri::command]
+pub fn validate_asset_name(name: String) -> AssetNameCheck {
+  match check_asset_name(&name) {
+    Ok(kind) => AssetNameCheck {
+      name,
+      valid: true,
+      kind: Some(kind.as_str().to_string()),
+      error: None,
+    },
+    Err(e) => AssetNameCheck { name, valid: false, kind: None, error: Some(e) },
+  }
+}
+
+#[tauri::command]
+pub fn validate_ipfs_hash(hash: String) -> IpfsHashCheck {
+  match check_ipfs_hash(&hash) {
+    Ok((kind, onchain)) => IpfsHashCheck {
+      hash,
+      valid: true,
+      kind: Some(kind.as_str().to_string()),
+      onchain: Some(onchain),
+      error: None,
+    },
+    Err(e) => IpfsHashCheck { hash, valid: false, kind: None, onchain: None, error: Some(e) },
+  }
+}
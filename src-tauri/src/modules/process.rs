@@ -8,6 +8,9 @@ use std::fs;
 use crate::modules::files::{data_dir, ensure_config, config_path};
 use crate::modules::utils::resolve_bin;
 use crate::modules::commands::run_cli;
+use crate::modules::coin_control::reapply_frozen_coins_when_ready;
+use crate::modules::safety_backup::snapshot_wallet;
+use crate::modules::wallets::{active_wallet_path, wallet_start_args};
 
 #[tauri::command]
 pub fn start_node() -> Result<(), String> {
@@ -43,19 +46,24 @@ pub fn start_node() -> Result<(), String> {
     use std::os::windows::process::CommandExt;
     cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
     cmd.arg(format!("-conf={}", cfg.to_string_lossy()))
-       .arg(format!("-datadir={}", dir.to_string_lossy()));
+       .arg(format!("-datadir={}", dir.to_string_lossy()))
+       .args(wallet_start_args());
   }
 
   #[cfg(unix)]
   {
      // Construct the full shell command string
      // Note: We quote the paths to be safe
-     let full_cmd = format!(
+     let mut full_cmd = format!(
          "\"{}\" -conf=\"{}\" -datadir=\"{}\" -daemon",
          daemon,
          cfg.to_string_lossy(),
          dir.to_string_lossy()
      );
+     // Wallet names are restricted to [A-Za-z0-9._-], safe to quote as-is
+     for arg in wallet_start_args() {
+         full_cmd.push_str(&format!(" \"{}\"", arg));
+     }
      
 
      
@@ -68,6 +76,9 @@ pub fn start_node() -> Result<(), String> {
 
         e.to_string()
     })?;
+
+  // Node locks don't survive a restart
+  reapply_frozen_coins_when_ready();
   Ok(())
 }
 
@@ -132,24 +143,24 @@ pub fn stop_node_internal() {
     thread::sleep(Duration::from_secs(2));
 }
 
+/// Replace the active wallet with the file at `path`. The current wallet is
+/// always copied to `wallet_backups/` first.
 #[tauri::command]
-pub fn restore_wallet(path: String, backup_existing: bool, restart_node: bool) -> Result<(), String> {
+pub fn restore_wallet(path: String, restart_node: bool) -> Result<(), String> {
   let dir = data_dir()?;
-    let wallet = dir.join("wallet.dat");
+    let wallet = active_wallet_path()?;
     if !Path::new(&path).exists() {
       return Err("Restore file not found.".to_string());
     }
-    
+
+    // Taken while the node still runs so backupwallet gives a consistent copy
+    snapshot_wallet("restore")?;
+
     // Stop node logic internal
     stop_node_internal();
     
-    if wallet.exists() && backup_existing {
-      let ts = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
-      let backup_dir = dir.join("wallet_backups");
-      let _ = fs::create_dir_all(&backup_dir);
-      let backup = backup_dir.join(format!("wallet_{}.bak", ts));
-      fs::rename(&wallet, backup).map_err(|e| e.to_string())?;
-    } else if wallet.exists() {
+    if wallet.exists() {
+      wait_for_lock_release(&dir);
       fs::remove_file(&wallet).map_err(|e| e.to_string())?;
     }
     fs::copy(path, wallet).map_err(|e| e.to_string())?;
@@ -160,20 +171,19 @@ pub fn restore_wallet(path: String, backup_existing: bool, restart_node: bool) -
   Ok(())
 }
 
+/// Start over with an empty active wallet. The current wallet is always copied
+/// to `wallet_backups/` first.
 #[tauri::command]
-pub fn create_new_wallet(backup_existing: bool, restart_node: bool) -> Result<(), String> {
+pub fn create_new_wallet(restart_node: bool) -> Result<(), String> {
   let dir = data_dir()?;
-    let wallet = dir.join("wallet.dat");
+    let wallet = active_wallet_path()?;
+
+    snapshot_wallet("new_wallet")?;
     
     stop_node_internal();
     
-    if wallet.exists() && backup_existing {
-      let ts = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
-      let backup_dir = dir.join("wallet_backups");
-      let _ = fs::create_dir_all(&backup_dir);
-      let backup = backup_dir.join(format!("wallet_{}.bak", ts));
-      fs::rename(&wallet, backup).map_err(|e| e.to_string())?;
-    } else if wallet.exists() {
+    if wallet.exists() {
+      wait_for_lock_release(&dir);
       fs::remove_file(&wallet).map_err(|e| e.to_string())?;
     }
   if restart_node {
@@ -0,0 +1,247 @@
+use std::collections::{HashMap, HashSet};
+
+// Import local modules
+use crate::modules::models::{TagBatchResult, TagResult, TaggedAddress};
+use crate::modules::utils::txid_from;
+use crate::modules::commands::run_cli;
+use crate::modules::files::ensure_config;
+use crate::modules::naming::{expect_asset_kind, onchain_ipfs_hash, AssetKind};
+use crate::modules::burns::take_quote;
+
+// Qualifiers (`#TAG`) are attached to addresses by whoever holds the
+// qualifier token; restricted asset verifiers are written in terms of them.
+// Every tag or untag is its own transaction.
+
+pub const MAX_QUALIFIER_QTY: u64 = 10;
+
+/// `#NAME` for `NAME` or `#NAME`. Sub-qualifiers (`#A/#B`) are left as given.
+pub fn qualifier_name(name: &str) -> String {
+  format!("#{}", name.trim().trim_start_matches('#'))
+}
+
+/// Addresses from pasted text or file lines: split on whitespace, commas and
+/// semicolons, duplicates dropped, order kept
+pub fn parse_address_list(entries: &[String]) -> Vec<String> {
+  let mut seen = Vec::new();
+  for entry in entries {
+    for addr in entry.split(|c: char| c.is_whitespace() || c == ',' || c == ';') {
+      let addr = addr.trim();
+      if !addr.is_empty() && !seen.iter().any(|a: &String| a == addr) {
+        seen.push(addr.to_string());
+      }
+    }
+  }
+  seen
+}
+
+fn optional(value: Option<String>) -> String {
+  value.map(|v| v.trim().to_string()).unwrap_or_default()
+}
+
+/// Tags currently attached to `address`
+pub fn tags_for_address(address: &str) -> Result<Vec<String>, String> {
+  let raw = run_cli(&[String::from("listtagsforaddress"), address.to_string()])?;
+  serde_json::from_str::<Vec<String>>(&raw).map_err(|e| e.to_string())
+}
+
+/// Current tags of this wallet's addresses among `addresses`, from a single
+/// `viewmytaggedaddresses` pass instead of one `listtagsforaddress` per
+/// address. The latest event of each address and tag decides. Empty when
+/// the node has no restricted asset support (yet).
+pub fn tags_by_address(addresses: &[String]) -> HashMap<String, Vec<String>> {
+  let wanted: HashSet<&str> = addresses.iter().map(|a| a.as_str()).collect();
+  let mut events = my_tag_events().unwrap_or_default();
+  events.sort_by(|a, b| a.time.cmp(&b.time));
+  let mut state: HashMap<(String, String), bool> = HashMap::new();
+  for event in events {
+    if wanted.contains(event.address.as_str()) {
+      state.insert((event.address, event.tag), event.assigned);
+    }
+  }
+  let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+  for ((address, tag), assigned) in state {
+    if assigned {
+      tags.entry(address).or_default().push(tag);
+    }
+  }
+  for list in tags.values_mut() {
+    list.sort();
+  }
+  tags
+}
+
+/// Issue a qualifier. Only 1 to 10 units of a qualifier can exist.
+#[tauri::command]
+pub fn issue_qualifier_asset(
+  name: String,
+  qty: u64,
+  to_address: Option<String>,
+  change_address: Option<String>,
+  ipfs: String,
+  quote_id: String,
+) -> Result<String, String> {
+  ensure_config()?;
+  if qty == 0 || qty > MAX_QUALIFIER_QTY {
+    return Err(format!("Qualifier quantity must be between 1 and {}", MAX_QUALIFIER_QTY));
+  }
+  let name = qualifier_name(&name);
+  expect_asset_kind(&name, &[AssetKind::Qualifier, AssetKind::SubQualifier], "issued as a qualifier")?;
+  let ipfs = onchain_ipfs_hash(&ipfs)?;
+  let mut args = vec![
+    String::from("issuequalifierasset"),
+    name,
+    qty.to_string(),
+    optional(to_address),
+    optional(change_address),
+  ];
+  if !ipfs.is_empty() {
+    args.push(String::from("true"));
+    args.push(ipfs);
+  }
+  take_quote(&quote_id, "issue", &args[1], 1)?;
+  Ok(txid_from(&run_cli(&args)?))
+}
+
+#[tauri::command]
+pub fn transfer_qualifier(
+  name: String,
+  qty: u64,
+  to_address: String,
+  change_address: Option<String>,
+  message: Option<String>,
+  expire_time: Option<u64>,
+) -> Result<String, String> {
+  ensure_config()?;
+  if qty == 0 || qty > MAX_QUALIFIER_QTY {
+    return Err(format!("Qualifier quantity must be between 1 and {}", MAX_QUALIFIER_QTY));
+  }
+  let message = optional(message);
+  let mut args = vec![
+    String::from("transferqualifier"),
+    qualifier_name(&name),
+    qty.to_string(),
+    to_address.trim().to_string(),
+    optional(change_address),
+  ];
+  if !message.is_empty() {
+    args.push(message);
+    args.push(expire_time.unwrap_or(0).to_string());
+  }
+  Ok(txid_from(&run_cli(&args)?))
+}
+
+/// Run `addtagtoaddress`/`removetagfromaddress` for each address. A failure
+/// is recorded and the batch goes on with the next address.
+fn tag_batch(
+  method: &str,
+  tag: &str,
+  addresses: &[String],
+  change_address: Option<String>,
+  asset_data: Option<String>,
+) -> Result<TagBatchResult, String> {
+  let tag = qualifier_name(tag);
+  let addresses = parse_address_list(addresses);
+  if addresses.is_empty() {
+    return Err("No addresses given".to_string());
+  }
+  let change = optional(change_address);
+  let data = optional(asset_data);
+
+  let mut results = Vec::new();
+  for address in addresses {
+    let mut args = vec![String::from(method), tag.clone(), address.clone(), change.clone()];
+    if !data.is_empty() {
+      args.push(data.clone());
+    }
+    results.push(match run_cli(&args) {
+      Ok(raw) => TagResult { address, txid: Some(txid_from(&raw)), error: None },
+      Err(e) => TagResult { address, txid: None, error: Some(e) },
+    });
+  }
+  let succeeded = results.iter().filter(|r| r.error.is_none()).count() as u32;
+  Ok(TagBatchResult {
+    tag,
+    failed: results.len() as u32 - succeeded,
+    succeeded,
+    results,
+  })
+}
+
+#[tauri::command]
+pub fn tag_addresses(
+  tag: String,
+  addresses: Vec<String>,
+  change_address: Option<String>,
+  asset_data: Option<String>,
+) -> Result<TagBatchResult, String> {
+  ensure_config()?;
+  tag_batch("addtagtoaddress", &tag, &addresses, change_address, asset_data)
+}
+
+#[tauri::command]
+pub fn untag_addresses(
+  tag: String,
+  addresses: Vec<String>,
+  change_address: Option<String>,
+  asset_data: Option<String>,
+) -> Result<TagBatchResult, String> {
+  ensure_config()?;
+  tag_batch("removetagfromaddress", &tag, &addresses, change_address, asset_data)
+}
+
+#[tauri::command]
+pub fn list_addresses_for_tag(tag: String) -> Result<Vec<String>, String> {
+  ensure_config()?;
+  let raw = run_cli(&[String::from("listaddressesfortag"), qualifier_name(&tag)])?;
+  serde_json::from_str::<Vec<String>>(&raw).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_tags_for_address(address: String) -> Result<Vec<String>, String> {
+  ensure_config()?;
+  tags_for_address(address.trim())
+}
+
+#[tauri::command]
+pub fn check_address_tag(address: String, tag: String) -> Result<bool, String> {
+  ensure_config()?;
+  let raw = run_cli(&[String::from("checkaddresstag"), address.trim().to_string(), qualifier_name(&tag)])?;
+  serde_json::from_str::<bool>(raw.trim()).map_err(|_| format!("Unexpected node reply: {}", raw))
+}
+
+/// Tag and untag events for addresses of this wallet
+fn my_tag_events() -> Result<Vec<TaggedAddress>, String> {
+  let raw = run_cli(&[String::from("viewmytaggedaddresses")])?;
+  let value: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  // Keys are display labels, with or without a trailing colon depending on the node version
+  let field = |row: &serde_json::Value, key: &str| -> Option<String> {
+    row.get(key)
+      .or_else(|| row.get(format!("{}:", key)))
+      .and_then(|v| v.as_str())
+      .map(|v| v.to_string())
+  };
+  Ok(value
+    .as_array()
+    .map(|rows| {
+      rows
+        .iter()
+        .map(|row| {
+          let removed = field(row, "Removed");
+          TaggedAddress {
+            address: field(row, "Address").unwrap_or_default(),
+            tag: field(row, "Tag Name").unwrap_or_default(),
+            assigned: removed.is_none(),
+            time: removed.or_else(|| field(row, "Assigned")),
+          }
+        })
+        .collect()
+    })
+    .unwrap_or_default())
+}
+
+/// Tag and untag events for addresses of this wallet
+#[tauri::command]
+pub fn list_my_tagged_addresses() -> Result<Vec<TaggedAddress>, String> {
+  ensure_config()?;
+  my_tag_events()
+}
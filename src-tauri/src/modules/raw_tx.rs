@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::Local;
+
+// Import local modules
+use crate::modules::models::{MempoolAcceptResult, PrevTxOut, RawTxInput, TxFile, TxFileResult};
+use crate::modules::commands::{run_cli, list_utxos};
+use crate::modules::files::{data_dir, ensure_config};
+use crate::modules::fees::plan_advanced_transaction;
+
+// Unsigned/signed transactions travel between machines as small JSON files
+// carrying the hex plus the previous outputs an offline signer needs.
+
+pub const TX_FILE_VERSION: u32 = 1;
+
+fn transactions_dir() -> Result<PathBuf, String> {
+  let dir = data_dir()?.join("transactions");
+  fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+  Ok(dir)
+}
+
+fn default_tx_path(prefix: &str) -> Result<PathBuf, String> {
+  let ts = Local::now().format("%Y%m%d_%H%M%S").to_string();
+  Ok(transactions_dir()?.join(format!("{}_{}.json", prefix, ts)))
+}
+
+pub fn read_tx_file(path: &Path) -> Result<TxFile, String> {
+  let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+  let tx: TxFile = serde_json::from_str(&content).map_err(|e| format!("Not a transaction file: {}", e))?;
+  if tx.version > TX_FILE_VERSION {
+    return Err(format!("Unsupported transaction file version {}", tx.version));
+  }
+  Ok(tx)
+}
+
+pub fn write_tx_file(path: &Path, tx: &TxFile) -> Result<(), String> {
+  let content = serde_json::to_string_pretty(tx).map_err(|e| e.to_string())?;
+  fs::write(path, content).map_err(|e| e.to_string())
+}
+
+/// scriptPubKey and amount of each input, needed by `signrawtransaction`
+/// on a machine that has never seen the funding transactions
+pub fn prev_outputs(inputs: &[RawTxInput]) -> Result<Vec<PrevTxOut>, String> {
+  let utxos = list_utxos()?;
+  let mut prevtxs = Vec::new();
+  for input in inputs {
+    if let Some(u) = utxos.iter().find(|u| u.txid == input.txid && u.vout == input.vout) {
+      if let Some(script) = &u.script_pub_key {
+        prevtxs.push(PrevTxOut {
+          txid: u.txid.clone(),
+          vout: u.vout,
+          script_pub_key: script.clone(),
+          redeem_script: None,
+          amount: u.amount,
+        });
+        continue;
+      }
+    }
+    let raw = run_cli(&[
+      String::from("gettxout"),
+      input.txid.clone(),
+      input.vout.to_string(),
+      String::from("true"),
+    ])?;
+    let out: serde_json::Value = serde_json::from_str(&raw)
+      .map_err(|_| format!("Input {}:{} is spent or unknown", input.txid, input.vout))?;
+    prevtxs.push(PrevTxOut {
+      txid: input.txid.clone(),
+      vout: input.vout,
+      script_pub_key: out["scriptPubKey"]["hex"].as_str().unwrap_or("").to_string(),
+      redeem_script: None,
+      amount: out["value"].as_f64().unwrap_or(0.0),
+    });
+  }
+  Ok(prevtxs)
+}
+
+/// Sign `hex` with the local wallet. Returns the new hex, whether it is
+/// fully signed, and any per-input errors reported by the node.
+pub fn sign_hex(hex: &str, prevtxs: &[PrevTxOut]) -> Result<(String, bool, Vec<String>), String> {
+  let prevtxs_json = serde_json::to_string(prevtxs).map_err(|e| e.to_string())?;
+  let raw = run_cli(&[String::from("signrawtransaction"), hex.to_string(), prevtxs_json])?;
+  let value: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  let signed = value["hex"].as_str().ok_or("No signed hex returned")?.to_string();
+  let complete = value["complete"].as_bool().unwrap_or(false);
+  let errors = value["errors"]
+    .as_array()
+    .map(|arr| {
+      arr
+        .iter()
+        .map(|e| {
+          format!(
+            "{}:{} {}",
+            e["txid"].as_str().unwrap_or("?"),
+            e["vout"].as_u64().unwrap_or(0),
+            e["error"].as_str().unwrap_or("unknown error")
+          )
+        })
+        .collect()
+    })
+    .unwrap_or_default();
+  Ok((signed, complete, errors))
+}
+
+pub fn mempool_accept(hex: &str) -> Result<MempoolAcceptResult, String> {
+  let txs = serde_json::to_string(&[hex]).map_err(|e| e.to_string())?;
+  let raw = run_cli(&[String::from("testmempoolaccept"), txs])?;
+  let value: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  let first = value.as_array().and_then(|a| a.first()).ok_or("Empty testmempoolaccept result")?;
+  Ok(MempoolAcceptResult {
+    txid: first["txid"].as_str().unwrap_or("").to_string(),
+    allowed: first["allowed"].as_bool().unwrap_or(false),
+    reject_reason: first["reject-reason"].as_str().map(|s| s.to_string()),
+  })
+}
+
+/// Build the transaction (fee and change included) and save it unsigned
+#[tauri::command]
+pub fn create_unsigned_transaction(
+  inputs: Vec<RawTxInput>,
+  outputs: HashMap<String, String>,
+  fee_rate: Option<f64>,
+  conf_target: Option<u32>,
+  path: Option<String>,
+  note: Option<String>,
+) -> Result<TxFileResult, String> {
+  ensure_config()?;
+  let plan = plan_advanced_transaction(&inputs, &outputs, fee_rate, conf_target)?;
+  let inputs_json = serde_json::to_string(&inputs).map_err(|e| e.to_string())?;
+  let outputs_json = serde_json::to_string(&plan.outputs).map_err(|e| e.to_string())?;
+  let hex = run_cli(&[String::from("createrawtransaction"), inputs_json, outputs_json])?;
+
+  let tx = TxFile {
+    version: TX_FILE_VERSION,
+    hex,
+    prevtxs: prev_outputs(&inputs)?,
+    created: Local::now().timestamp() as u64,
+    signed: false,
+    complete: false,
+    note: note.unwrap_or_default(),
+  };
+  let dest = match path.filter(|p| !p.trim().is_empty()) {
+    Some(p) => PathBuf::from(p),
+    None => default_tx_path("unsigned")?,
+  };
+  write_tx_file(&dest, &tx)?;
+
+  Ok(TxFileResult {
+    path: dest.to_string_lossy().to_string(),
+    tx,
+    plan: Some(plan),
+    errors: Vec::new(),
+  })
+}
+
+#[tauri::command]
+pub fn load_transaction_file(path: String) -> Result<TxFile, String> {
+  read_tx_file(Path::new(&path))
+}
+
+#[tauri::command]
+pub fn decode_transaction(hex: String) -> Result<serde_json::Value, String> {
+  ensure_config()?;
+  let raw = run_cli(&[String::from("decoderawtransaction"), hex.trim().to_string()])?;
+  serde_json::from_str(&raw).map_err(|e| e.to_string())
+}
+
+/// Sign a saved transaction file with this wallet and write the result
+/// next to it (or to `out_path`). Works on an offline/air-gapped node as
+/// long as the file carries the previous outputs.
+#[tauri::command]
+pub fn sign_transaction_file(path: String, out_path: Option<String>) -> Result<TxFileResult, String> {
+  ensure_config()?;
+  let mut tx = read_tx_file(Path::new(&path))?;
+  let (hex, complete, errors) = sign_hex(&tx.hex, &tx.prevtxs)?;
+  if hex == tx.hex {
+    return Err("Wallet could not sign any input of this transaction".to_string());
+  }
+  tx.hex = hex;
+  tx.signed = true;
+  tx.complete = complete;
+
+  let dest = match out_path.filter(|p| !p.trim().is_empty()) {
+    Some(p) => PathBuf::from(p),
+    None => default_tx_path("signed")?,
+  };
+  write_tx_file(&dest, &tx)?;
+
+  Ok(TxFileResult {
+    path: dest.to_string_lossy().to_string(),
+    tx,
+    plan: None,
+    errors,
+  })
+}
+
+#[tauri::command]
+pub fn test_transaction(hex: String) -> Result<MempoolAcceptResult, String> {
+  ensure_config()?;
+  mempool_accept(hex.trim())
+}
+
+/// Broadcast a signed transaction, checking it with `testmempoolaccept` first
+#[tauri::command]
+pub fn broadcast_signed_transaction(hex: String) -> Result<String, String> {
+  ensure_config()?;
+  let hex = hex.trim().to_string();
+  let check = mempool_accept(&hex)?;
+  if !check.allowed {
+    return Err(format!(
+      "Transaction rejected by mempool: {}",
+      check.reject_reason.unwrap_or_else(|| "unknown reason".to_string())
+    ));
+  }
+  run_cli(&[String::from("sendrawtransaction"), hex])
+}
+
+#[tauri::command]
+pub fn broadcast_transaction_file(path: String) -> Result<String, String> {
+  let tx = read_tx_file(Path::new(&path))?;
+  if !tx.complete {
+    return Err("Transaction file is not fully signed".to_string());
+  }
+  broadcast_signed_transaction(tx.hex)
+}
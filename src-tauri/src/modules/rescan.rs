@@ -0,0 +1,171 @@
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use tauri::Emitter;
+
+// Import local modules
+use crate::modules::models::{RescanProgress, RescanStatus};
+use crate::modules::commands::run_cli;
+use crate::modules::files::{data_dir, ensure_config};
+
+// `rescanblockchain` holds the RPC call open until the scan ends, so it runs
+// on its own thread while a second loop reads progress from debug.log.
+// `getwalletinfo` waits on the wallet lock during a scan and cannot be used
+// for that.
+
+static RESCAN_RUNNING: AtomicBool = AtomicBool::new(false);
+static RESCAN_LOG_OFFSET: AtomicU64 = AtomicU64::new(0);
+
+fn debug_log_path() -> Result<PathBuf, String> {
+  Ok(data_dir()?.join("debug.log"))
+}
+
+/// Current size of debug.log, to ignore progress lines from earlier scans
+pub fn log_offset() -> u64 {
+  debug_log_path()
+    .ok()
+    .and_then(|p| fs::metadata(p).ok())
+    .map(|m| m.len())
+    .unwrap_or(0)
+}
+
+/// Height and progress from the last "Still rescanning. At block X.
+/// Progress=Y" line written after `offset`
+pub fn rescan_progress_since(offset: u64) -> Option<(u64, f64)> {
+  let mut file = fs::File::open(debug_log_path().ok()?).ok()?;
+  let len = file.metadata().ok()?.len();
+  if len <= offset {
+    return None;
+  }
+  // Only the last 16 KiB matter, the node logs progress about once a minute
+  let start = offset.max(len.saturating_sub(16 * 1024));
+  file.seek(SeekFrom::Start(start)).ok()?;
+  let mut buf = Vec::new();
+  file.read_to_end(&mut buf).ok()?;
+  let text = String::from_utf8_lossy(&buf);
+  let line = text.lines().rev().find(|l| l.contains("Still rescanning."))?;
+  let height = line.split("At block ").nth(1)?.split('.').next()?.trim().parse().ok()?;
+  let progress = line.split("Progress=").nth(1)?.trim().parse().ok()?;
+  Some((height, progress))
+}
+
+pub fn is_rescan_running() -> bool {
+  RESCAN_RUNNING.load(Ordering::SeqCst)
+}
+
+/// Run `rescanblockchain` in the background and report `rescan-progress`
+/// events until it finishes or is aborted
+pub fn spawn_rescan(app_handle: tauri::AppHandle, start_height: Option<u64>, stop_height: Option<u64>) -> Result<(), String> {
+  if let (Some(start), Some(stop)) = (start_height, stop_height) {
+    if stop < start {
+      return Err("Stop height must not be below the start height".to_string());
+    }
+  }
+  if RESCAN_RUNNING.swap(true, Ordering::SeqCst) {
+    return Err("A rescan is already running".to_string());
+  }
+
+  let mut args = vec![String::from("rescanblockchain")];
+  if start_height.is_some() || stop_height.is_some() {
+    args.push(start_height.unwrap_or(0).to_string());
+  }
+  if let Some(stop) = stop_height {
+    args.push(stop.to_string());
+  }
+
+  let offset = log_offset();
+  RESCAN_LOG_OFFSET.store(offset, Ordering::SeqCst);
+  thread::spawn(move || {
+    let emit = |progress: Option<f64>, height: Option<u64>, done: bool, aborted: bool, error: Option<String>| {
+      let _ = app_handle.emit("rescan-progress", RescanProgress {
+        progress,
+        height,
+        start_height,
+        stop_height,
+        done,
+        aborted,
+        error,
+      });
+    };
+    emit(Some(0.0), start_height, false, false, None);
+
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+      let _ = tx.send(run_cli(&args));
+    });
+
+    let result = loop {
+      match rx.recv_timeout(Duration::from_secs(2)) {
+        Ok(result) => break result,
+        Err(mpsc::RecvTimeoutError::Timeout) => {
+          if let Some((height, progress)) = rescan_progress_since(offset) {
+            emit(Some(progress), Some(height), false, false, None);
+          }
+        }
+        Err(mpsc::RecvTimeoutError::Disconnected) => break Err("Rescan thread stopped unexpectedly".to_string()),
+      }
+    };
+
+    match result {
+      Ok(raw) => {
+        let value: serde_json::Value = serde_json::from_str(&raw).unwrap_or_default();
+        emit(Some(1.0), value["stop_height"].as_u64(), true, false, None);
+      }
+      Err(e) if e.to_lowercase().contains("abort") => emit(None, None, true, true, None),
+      Err(e) => emit(None, None, true, false, Some(e)),
+    }
+    RESCAN_RUNNING.store(false, Ordering::SeqCst);
+  });
+  Ok(())
+}
+
+#[tauri::command]
+pub fn start_rescan(app_handle: tauri::AppHandle, start_height: Option<u64>, stop_height: Option<u64>) -> Result<(), String> {
+  ensure_config()?;
+  spawn_rescan(app_handle, start_height, stop_height)
+}
+
+#[tauri::command]
+pub fn abort_rescan() -> Result<bool, String> {
+  ensure_config()?;
+  let raw = run_cli(&[String::from("abortrescan")])?;
+  Ok(raw.trim() == "true")
+}
+
+/// Progress of a rescan started here, or of one the node is running on its
+/// own (reported through `getwalletinfo`'s `scanning` field)
+#[tauri::command]
+pub fn get_rescan_status() -> Result<RescanStatus, String> {
+  ensure_config()?;
+  if is_rescan_running() {
+    let last = rescan_progress_since(RESCAN_LOG_OFFSET.load(Ordering::SeqCst));
+    return Ok(RescanStatus {
+      running: true,
+      progress: last.map(|(_, p)| p),
+      height: last.map(|(h, _)| h),
+    });
+  }
+  let raw = run_cli(&[String::from("getwalletinfo")])?;
+  let info: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  let scanning = &info["scanning"];
+  Ok(RescanStatus {
+    running: scanning.is_object(),
+    progress: scanning["progress"].as_f64(),
+    height: None,
+  })
+}
+
+/// Start a background rescan after an import made with rescan=false. The
+/// import has already succeeded, so a rescan that cannot start is only logged.
+pub fn rescan_after_import(app_handle: tauri::AppHandle, rescan: bool, rescan_from: Option<u64>) {
+  if !rescan {
+    return;
+  }
+  if let Err(e) = spawn_rescan(app_handle, Some(rescan_from.unwrap_or(0)), None) {
+    log::warn!("Rescan after import not started: {}", e);
+  }
+}
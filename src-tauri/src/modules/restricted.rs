@@ -0,0 +1,312 @@
+// Import local modules
+use crate::modules::models::{AddressEligibility, RestrictedAddress, RestrictedAssetInfo};
+use crate::modules::utils::{parse_asset_qty, txid_from};
+use crate::modules::commands::run_cli;
+use crate::modules::files::ensure_config;
+use crate::modules::verifier::{evaluate_for_address, parse_verifier};
+use crate::modules::naming::{expect_asset_kind, onchain_ipfs_hash, AssetKind};
+use crate::modules::burns::take_quote;
+
+// Restricted (`$NAME`) assets can only be sent to addresses that satisfy
+// the asset's verifier string. The issuer (holder of `NAME!`) can freeze
+// single addresses or the whole asset.
+
+/// `$NAME` for `NAME` or `$NAME`
+pub fn restricted_name(name: &str) -> String {
+  format!("${}", name.trim().trim_start_matches('$'))
+}
+
+fn optional(value: Option<String>) -> String {
+  value.map(|v| v.trim().to_string()).unwrap_or_default()
+}
+
+fn parse_bool(raw: &str) -> Result<bool, String> {
+  serde_json::from_str::<bool>(raw.trim()).map_err(|_| format!("Unexpected node reply: {}", raw))
+}
+
+fn parse_names(raw: &str) -> Result<Vec<String>, String> {
+  serde_json::from_str::<Vec<String>>(raw).map_err(|e| e.to_string())
+}
+
+/// Trailing `change_address` and `asset_data` shared by the freeze calls
+fn push_change_and_data(args: &mut Vec<String>, change_address: Option<String>, asset_data: Option<String>) {
+  let data = optional(asset_data);
+  args.push(optional(change_address));
+  if !data.is_empty() {
+    args.push(data);
+  }
+}
+
+/// Verifier string of a restricted asset, `None` if the node knows none
+pub fn verifier_for(name: &str) -> Option<String> {
+  run_cli(&[String::from("getverifierstring"), restricted_name(name)])
+    .ok()
+    .map(|v| v.trim().trim_matches('"').to_string())
+    .filter(|v| !v.is_empty())
+}
+
+pub fn is_globally_frozen(name: &str) -> Result<bool, String> {
+  parse_bool(&run_cli(&[String::from("checkglobalrestriction"), restricted_name(name)])?)
+}
+
+pub fn is_address_frozen(address: &str, name: &str) -> Result<bool, String> {
+  parse_bool(&run_cli(&[
+    String::from("checkaddressrestriction"),
+    address.trim().to_string(),
+    restricted_name(name),
+  ])?)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn issue_restricted_asset(
+  name: String,
+  qty: String,
+  verifier: String,
+  to_address: String,
+  change_address: Option<String>,
+  units: u8,
+  reissuable: bool,
+  ipfs: String,
+  quote_id: String,
+) -> Result<String, String> {
+  ensure_config()?;
+  if units > 8 {
+    return Err("Units must be between 0 and 8".to_string());
+  }
+  let name = restricted_name(&name);
+  expect_asset_kind(&name, &[AssetKind::Restricted], "issued as a restricted asset")?;
+  let ipfs = onchain_ipfs_hash(&ipfs)?;
+  let verifier = parse_verifier(&verifier)?.to_string();
+  if to_address.trim().is_empty() {
+    return Err("Restricted assets must be issued to an address that satisfies the verifier".to_string());
+  }
+  let mut args = vec![
+    String::from("issuerestrictedasset"),
+    name,
+    parse_asset_qty(&qty)?,
+    verifier,
+    to_address.trim().to_string(),
+    optional(change_address),
+    units.to_string(),
+    reissuable.to_string(),
+  ];
+  if !ipfs.is_empty() {
+    args.push(String::from("true"));
+    args.push(ipfs);
+  }
+  take_quote(&quote_id, "issue", &args[1], 1)?;
+  Ok(txid_from(&run_cli(&args)?))
+}
+
+/// Reissue a restricted asset. `new_units` of -1 keeps the current units.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn reissue_restricted_asset(
+  name: String,
+  qty: String,
+  to_address: String,
+  change_verifier: bool,
+  new_verifier: String,
+  change_address: Option<String>,
+  new_units: Option<i32>,
+  reissuable: bool,
+  new_ipfs: String,
+  quote_id: String,
+) -> Result<String, String> {
+  ensure_config()?;
+  let units = new_units.unwrap_or(-1);
+  if !(-1..=8).contains(&units) {
+    return Err("Units must be between 0 and 8".to_string());
+  }
+  let name = restricted_name(&name);
+  expect_asset_kind(&name, &[AssetKind::Restricted], "reissued as a restricted asset")?;
+  let new_ipfs = onchain_ipfs_hash(&new_ipfs)?;
+  let new_verifier = if change_verifier { parse_verifier(&new_verifier)?.to_string() } else { String::new() };
+  // Reissuing zero is allowed here: it is how the verifier, units or IPFS change alone
+  let qty = if qty.trim().is_empty() || qty.trim().parse::<f64>() == Ok(0.0) {
+    String::from("0")
+  } else {
+    parse_asset_qty(&qty)?
+  };
+  let mut args = vec![
+    String::from("reissuerestrictedasset"),
+    name,
+    qty,
+    to_address.trim().to_string(),
+    change_verifier.to_string(),
+    new_verifier,
+    optional(change_address),
+    units.to_string(),
+    reissuable.to_string(),
+  ];
+  if !new_ipfs.is_empty() {
+    args.push(new_ipfs);
+  }
+  take_quote(&quote_id, "reissue", &args[1], 1)?;
+  Ok(txid_from(&run_cli(&args)?))
+}
+
+#[tauri::command]
+pub fn freeze_address(
+  asset_name: String,
+  address: String,
+  change_address: Option<String>,
+  asset_data: Option<String>,
+) -> Result<String, String> {
+  ensure_config()?;
+  let mut args = vec![String::from("freezeaddress"), restricted_name(&asset_name), address.trim().to_string()];
+  push_change_and_data(&mut args, change_address, asset_data);
+  Ok(txid_from(&run_cli(&args)?))
+}
+
+#[tauri::command]
+pub fn unfreeze_address(
+  asset_name: String,
+  address: String,
+  change_address: Option<String>,
+  asset_data: Option<String>,
+) -> Result<String, String> {
+  ensure_config()?;
+  let mut args = vec![String::from("unfreezeaddress"), restricted_name(&asset_name), address.trim().to_string()];
+  push_change_and_data(&mut args, change_address, asset_data);
+  Ok(txid_from(&run_cli(&args)?))
+}
+
+/// Freeze every holder of the asset at once
+#[tauri::command]
+pub fn freeze_restricted_asset(
+  asset_name: String,
+  change_address: Option<String>,
+  asset_data: Option<String>,
+) -> Result<String, String> {
+  ensure_config()?;
+  let mut args = vec![String::from("freezerestrictedasset"), restricted_name(&asset_name)];
+  push_change_and_data(&mut args, change_address, asset_data);
+  Ok(txid_from(&run_cli(&args)?))
+}
+
+#[tauri::command]
+pub fn unfreeze_restricted_asset(
+  asset_name: String,
+  change_address: Option<String>,
+  asset_data: Option<String>,
+) -> Result<String, String> {
+  ensure_config()?;
+  let mut args = vec![String::from("unfreezerestrictedasset"), restricted_name(&asset_name)];
+  push_change_and_data(&mut args, change_address, asset_data);
+  Ok(txid_from(&run_cli(&args)?))
+}
+
+#[tauri::command]
+pub fn get_restricted_asset_info(name: String) -> Result<RestrictedAssetInfo, String> {
+  ensure_config()?;
+  let name = restricted_name(&name);
+  Ok(RestrictedAssetInfo {
+    verifier: verifier_for(&name),
+    globally_frozen: is_globally_frozen(&name)?,
+    name,
+  })
+}
+
+/// Restricted assets frozen for everyone
+#[tauri::command]
+pub fn list_global_restrictions() -> Result<Vec<String>, String> {
+  ensure_config()?;
+  parse_names(&run_cli(&[String::from("listglobalrestrictions")])?)
+}
+
+/// Restricted assets `address` is frozen for
+#[tauri::command]
+pub fn list_address_restrictions(address: String) -> Result<Vec<String>, String> {
+  ensure_config()?;
+  parse_names(&run_cli(&[String::from("listaddressrestrictions"), address.trim().to_string()])?)
+}
+
+/// Freeze and unfreeze events for addresses of this wallet
+#[tauri::command]
+pub fn list_my_restricted_addresses() -> Result<Vec<RestrictedAddress>, String> {
+  ensure_config()?;
+  let raw = run_cli(&[String::from("viewmyrestrictedaddresses")])?;
+  let value: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  // Keys are display labels, with or without a trailing colon depending on the node version
+  let field = |row: &serde_json::Value, key: &str| -> Option<String> {
+    row.get(key)
+      .or_else(|| row.get(format!("{}:", key)))
+      .and_then(|v| v.as_str())
+      .map(|v| v.to_string())
+  };
+  Ok(value
+    .as_array()
+    .map(|rows| {
+      rows
+        .iter()
+        .map(|row| {
+          let derestricted = field(row, "Derestricted");
+          RestrictedAddress {
+            address: field(row, "Address").unwrap_or_default(),
+            asset_name: field(row, "Asset Name").unwrap_or_default(),
+            restricted: derestricted.is_none(),
+            time: derestricted.or_else(|| field(row, "Restricted")),
+          }
+        })
+        .collect()
+    })
+    .unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn check_address_restriction(address: String, asset_name: String) -> Result<bool, String> {
+  ensure_config()?;
+  is_address_frozen(&address, &asset_name)
+}
+
+/// Whether `address` may receive the restricted asset, with the reasons if not
+#[tauri::command]
+pub fn check_address_eligibility(address: String, asset_name: String) -> Result<AddressEligibility, String> {
+  ensure_config()?;
+  let name = restricted_name(&asset_name);
+  let address = address.trim().to_string();
+  let frozen = is_address_frozen(&address, &name)?;
+  let globally_frozen = is_globally_frozen(&name)?;
+  let verifier = verifier_for(&name);
+  let evaluation = verifier.as_deref().map(|v| evaluate_for_address(v, &address));
+  let verifier_ok = match evaluation {
+    None => Some(true),
+    Some(Ok(ref e)) => Some(e.satisfied),
+    Some(Err(_)) => None,
+  };
+
+  let mut reasons = Vec::new();
+  if frozen {
+    reasons.push(format!("{} is frozen for {}", address, name));
+  }
+  if globally_frozen {
+    reasons.push(format!("{} is frozen for all addresses", name));
+  }
+  match evaluation {
+    Some(Ok(ref e)) if !e.satisfied => {
+      let mut why = Vec::new();
+      if !e.missing.is_empty() {
+        why.push(format!("missing {}", e.missing.join(", ")));
+      }
+      if !e.forbidden.is_empty() {
+        why.push(format!("tagged {}", e.forbidden.join(", ")));
+      }
+      reasons.push(format!("Address does not satisfy {} ({})", e.verifier, why.join("; ")));
+    }
+    Some(Err(ref err)) => reasons.push(format!("Verifier could not be evaluated: {}", err)),
+    _ => {}
+  }
+
+  Ok(AddressEligibility {
+    eligible: !frozen && !globally_frozen && verifier_ok == Some(true),
+    address,
+    asset_name: name,
+    frozen,
+    globally_frozen,
+    verifier,
+    verifier_ok,
+    reasons,
+  })
+}
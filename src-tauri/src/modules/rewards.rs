@@ -0,0 +1,445 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+use chrono::Local;
+use tauri::Emitter;
+use uuid::Uuid;
+
+// Import local modules
+use crate::modules::models::{RewardPayout, RewardPlan, RewardPreview, SnapshotHolder, SnapshotRequest};
+use crate::modules::utils::{to_sats, parse_amount_sats, format_sats, COIN};
+use crate::modules::commands::run_cli;
+use crate::modules::files::{data_dir, ensure_config};
+use crate::modules::qualifiers::parse_address_list;
+use crate::modules::watch_only::BASE_ASSET;
+
+// Dividends in four steps: ask the node to snapshot the holders of an
+// asset at a future height, wait for that height, dry-run the payout and
+// then let the node distribute it. Plans are kept in `rewards.json` and
+// move through scheduled -> snapshot_ready -> distributing -> completed
+// (or failed / cancelled).
+
+const TRACK_INTERVAL_SECS: u64 = 60;
+
+static TRACKER_RUNNING: AtomicBool = AtomicBool::new(false);
+/// Held for every read-modify-write of `rewards.json`
+static PLANS_LOCK: Mutex<()> = Mutex::new(());
+
+fn lock_plans() -> Result<MutexGuard<'static, ()>, String> {
+  PLANS_LOCK.lock().map_err(|_| "Reward plans are unavailable".to_string())
+}
+
+fn rewards_path() -> Result<PathBuf, String> {
+  Ok(data_dir()?.join("rewards.json"))
+}
+
+fn load_reward_plans() -> Result<Vec<RewardPlan>, String> {
+  let path = rewards_path()?;
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+  let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+  let plans: Vec<RewardPlan> = serde_json::from_str(&content).unwrap_or_default();
+  Ok(plans)
+}
+
+fn save_reward_plans(plans: &[RewardPlan]) -> Result<(), String> {
+  let path = rewards_path()?;
+  let content = serde_json::to_string_pretty(plans).map_err(|e| e.to_string())?;
+  fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+/// Callers hold `PLANS_LOCK`
+fn update_plan(plan: &RewardPlan) -> Result<(), String> {
+  let mut plans = load_reward_plans()?;
+  match plans.iter_mut().find(|p| p.id == plan.id) {
+    Some(existing) => *existing = plan.clone(),
+    None => plans.push(plan.clone()),
+  }
+  save_reward_plans(&plans)
+}
+
+/// Store a plan refreshed while it was in `from`. Skipped when the stored
+/// plan has moved on meanwhile (cancelled, started, removed), so a stale
+/// copy never overwrites it.
+fn store_refreshed(plan: &RewardPlan, from: &str) -> Result<bool, String> {
+  let _guard = lock_plans()?;
+  let mut plans = load_reward_plans()?;
+  match plans.iter_mut().find(|p| p.id == plan.id) {
+    Some(existing) if existing.status == from => *existing = plan.clone(),
+    _ => return Ok(false),
+  }
+  save_reward_plans(&plans)?;
+  Ok(true)
+}
+
+fn is_active(plan: &RewardPlan) -> bool {
+  plan.status == "scheduled" || plan.status == "distributing"
+}
+
+fn find_plan(id: &str) -> Result<RewardPlan, String> {
+  load_reward_plans()?
+    .into_iter()
+    .find(|p| p.id == id)
+    .ok_or_else(|| format!("Unknown reward plan {}", id))
+}
+
+fn block_height() -> Result<u64, String> {
+  run_cli(&[String::from("getblockcount")])?
+    .trim()
+    .parse()
+    .map_err(|_| "Could not read the block height".to_string())
+}
+
+/// Holders of `asset` at `height`, from a snapshot the node has taken.
+/// Amounts are in sats.
+pub fn fetch_snapshot(asset: &str, height: u64) -> Result<Vec<(String, u64)>, String> {
+  let raw = run_cli(&[String::from("getsnapshot"), asset.to_string(), height.to_string()])?;
+  let value: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  let owners = value["owners"].as_array().ok_or("The snapshot has no holder list")?;
+  Ok(owners
+    .iter()
+    .filter_map(|o| {
+      let address = o["address"].as_str()?.to_string();
+      Some((address, to_sats(o["amount_owned"].as_f64().unwrap_or(0.0))))
+    })
+    .collect())
+}
+
+/// Smallest amount of `asset` that can be sent, in sats
+fn unit_step(asset: &str) -> Result<u64, String> {
+  if asset == BASE_ASSET {
+    return Ok(1);
+  }
+  let raw = run_cli(&[String::from("getassetdata"), asset.to_string()])?;
+  let value: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  let units = value["units"].as_u64().unwrap_or(0).min(8) as u32;
+  Ok(COIN / 10u64.pow(units))
+}
+
+/// Pro-rata payouts of `gross` over `holders`, rounded down to `step`.
+/// Returns the payouts (zero payouts dropped) and what is left over.
+pub fn split_reward(holders: &[(String, u64)], gross: u64, step: u64) -> (Vec<(String, u64, u64)>, u64) {
+  let total: u128 = holders.iter().map(|(_, h)| *h as u128).sum();
+  if total == 0 {
+    return (Vec::new(), gross);
+  }
+  let step = step.max(1);
+  let mut payouts = Vec::new();
+  let mut paid = 0u64;
+  for (address, holding) in holders {
+    let share = (gross as u128 * *holding as u128 / total) as u64;
+    let share = share - share % step;
+    if share > 0 {
+      paid += share;
+      payouts.push((address.clone(), *holding, share));
+    }
+  }
+  (payouts, gross - paid)
+}
+
+fn distribute_args(plan: &RewardPlan, dry_run: bool) -> Vec<String> {
+  vec![
+    String::from("distributereward"),
+    plan.asset_name.clone(),
+    plan.snapshot_height.to_string(),
+    plan.distribution_asset.clone(),
+    plan.gross_amount.clone(),
+    plan.exception_addresses.join(","),
+    plan.change_address.clone().unwrap_or_default(),
+    dry_run.to_string(),
+  ]
+}
+
+/// Outcome from a `getdistributestatus` reply: its status field when it
+/// is an object, the text itself otherwise. `None` while still running.
+fn distribution_state(raw: &str) -> Option<&'static str> {
+  let status = match serde_json::from_str::<serde_json::Value>(raw) {
+    Ok(serde_json::Value::Object(obj)) => obj
+      .iter()
+      .find(|(k, _)| k.eq_ignore_ascii_case("status"))
+      .map(|(_, v)| v.as_str().map(|s| s.to_string()).unwrap_or_else(|| v.to_string()))?,
+    Ok(serde_json::Value::String(s)) => s,
+    _ => raw.to_string(),
+  }
+  .to_lowercase();
+  if status.contains("complete") || status.contains("success") {
+    Some("completed")
+  } else if status.contains("fail") || status.contains("error") {
+    Some("failed")
+  } else {
+    None
+  }
+}
+
+/// Move a plan along: scheduled plans become ready once the snapshot
+/// exists, distributing plans take the node's distribution status.
+fn refresh_plan(plan: &mut RewardPlan) -> bool {
+  let before = (plan.status.clone(), plan.node_status.clone());
+  match plan.status.as_str() {
+    "scheduled" => {
+      let reached = block_height().map(|h| h >= plan.snapshot_height).unwrap_or(false);
+      if reached && fetch_snapshot(&plan.asset_name, plan.snapshot_height).is_ok() {
+        plan.status = String::from("snapshot_ready");
+      }
+    }
+    "distributing" => {
+      let mut args = distribute_args(plan, false);
+      args[0] = String::from("getdistributestatus");
+      args.truncate(6);
+      match run_cli(&args) {
+        Ok(raw) => {
+          match distribution_state(&raw) {
+            Some("completed") => plan.status = String::from("completed"),
+            Some("failed") => {
+              plan.status = String::from("failed");
+              plan.error = Some(raw.clone());
+            }
+            _ => {}
+          }
+          plan.node_status = Some(raw);
+        }
+        Err(e) => plan.node_status = Some(e),
+      }
+    }
+    _ => {}
+  }
+  (plan.status.clone(), plan.node_status.clone()) != before
+}
+
+/// Poll active plans in the background and emit `reward-status` whenever
+/// one changes. Stops once no plan is waiting on the node.
+fn spawn_tracker(app_handle: tauri::AppHandle) {
+  if TRACKER_RUNNING.swap(true, Ordering::SeqCst) {
+    return;
+  }
+  thread::spawn(move || {
+    loop {
+      let plans = load_reward_plans().unwrap_or_default();
+      for mut plan in plans.into_iter().filter(is_active) {
+        let from = plan.status.clone();
+        if refresh_plan(&mut plan) && store_refreshed(&plan, &from).unwrap_or(false) {
+          let _ = app_handle.emit("reward-status", plan);
+        }
+      }
+      if !load_reward_plans().unwrap_or_default().iter().any(is_active) {
+        break;
+      }
+      thread::sleep(Duration::from_secs(TRACK_INTERVAL_SECS));
+    }
+    TRACKER_RUNNING.store(false, Ordering::SeqCst);
+  });
+}
+
+/// Ask the node to snapshot `asset_name` at a future `snapshot_height` and
+/// record the planned distribution
+#[tauri::command]
+pub fn schedule_reward(
+  app_handle: tauri::AppHandle,
+  asset_name: String,
+  snapshot_height: u64,
+  distribution_asset: String,
+  gross_amount: String,
+  exception_addresses: Vec<String>,
+  change_address: Option<String>,
+) -> Result<RewardPlan, String> {
+  ensure_config()?;
+  let asset_name = asset_name.trim().to_string();
+  let distribution_asset = match distribution_asset.trim() {
+    "" => BASE_ASSET.to_string(),
+    name => name.to_string(),
+  };
+  let gross = parse_amount_sats(&gross_amount)?;
+  if gross == 0 {
+    return Err("Distribution amount must be greater than zero".to_string());
+  }
+  let height = block_height()?;
+  if snapshot_height <= height {
+    return Err(format!("Snapshot height must be in the future (current height {})", height));
+  }
+  run_cli(&[String::from("requestsnapshot"), asset_name.clone(), snapshot_height.to_string()])?;
+
+  let plan = RewardPlan {
+    id: Uuid::new_v4().to_string(),
+    asset_name,
+    snapshot_height,
+    distribution_asset,
+    gross_amount: format_sats(gross),
+    exception_addresses: parse_address_list(&exception_addresses),
+    change_address: change_address.map(|c| c.trim().to_string()).filter(|c| !c.is_empty()),
+    status: String::from("scheduled"),
+    created: Local::now().timestamp() as u64,
+    node_status: None,
+    error: None,
+  };
+  {
+    let _guard = lock_plans()?;
+    update_plan(&plan)?;
+  }
+  spawn_tracker(app_handle);
+  Ok(plan)
+}
+
+/// All plans, brought up to date with the node
+#[tauri::command]
+pub fn list_reward_plans(app_handle: tauri::AppHandle) -> Result<Vec<RewardPlan>, String> {
+  ensure_config()?;
+  for mut plan in load_reward_plans()?.into_iter().filter(is_active) {
+    let from = plan.status.clone();
+    if refresh_plan(&mut plan) {
+      store_refreshed(&plan, &from)?;
+    }
+  }
+  let plans = load_reward_plans()?;
+  if plans.iter().any(is_active) {
+    spawn_tracker(app_handle);
+  }
+  Ok(plans)
+}
+
+/// Holders at the snapshot height, largest first
+#[tauri::command]
+pub fn get_snapshot_holders(asset_name: String, block_height: u64) -> Result<Vec<SnapshotHolder>, String> {
+  ensure_config()?;
+  let mut holders = fetch_snapshot(asset_name.trim(), block_height)?;
+  holders.sort_by_key(|(_, amount)| std::cmp::Reverse(*amount));
+  Ok(holders
+    .into_iter()
+    .map(|(address, amount)| SnapshotHolder { address, amount: format_sats(amount) })
+    .collect())
+}
+
+/// Work out who gets what, and ask the node for its own dry run
+#[tauri::command]
+pub fn preview_reward(id: String) -> Result<RewardPreview, String> {
+  ensure_config()?;
+  let plan = find_plan(&id)?;
+  let holders = fetch_snapshot(&plan.asset_name, plan.snapshot_height)
+    .map_err(|e| format!("Snapshot at height {} is not available yet: {}", plan.snapshot_height, e))?;
+  let holder_count = holders.len();
+  let (excluded, eligible): (Vec<_>, Vec<_>) = holders
+    .into_iter()
+    .partition(|(address, _)| plan.exception_addresses.contains(address));
+
+  let gross = parse_amount_sats(&plan.gross_amount)?;
+  let (payouts, remainder) = split_reward(&eligible, gross, unit_step(&plan.distribution_asset)?);
+  let node_result = run_cli(&distribute_args(&plan, true)).unwrap_or_else(|e| e);
+
+  Ok(RewardPreview {
+    plan_id: plan.id,
+    asset_name: plan.asset_name,
+    snapshot_height: plan.snapshot_height,
+    distribution_asset: plan.distribution_asset,
+    gross_amount: plan.gross_amount,
+    distributed: format_sats(gross - remainder),
+    remainder: format_sats(remainder),
+    holders: holder_count,
+    recipients: payouts.len(),
+    excluded: excluded.into_iter().map(|(address, _)| address).collect(),
+    payouts: payouts
+      .into_iter()
+      .map(|(address, holding, amount)| RewardPayout {
+        address,
+        holding: format_sats(holding),
+        amount: format_sats(amount),
+      })
+      .collect(),
+    node_result: Some(node_result),
+  })
+}
+
+/// Start the distribution. The node sends the payouts in batches; the
+/// tracker follows it through `getdistributestatus`.
+#[tauri::command]
+pub fn execute_reward(app_handle: tauri::AppHandle, id: String) -> Result<RewardPlan, String> {
+  ensure_config()?;
+  let guard = lock_plans()?;
+  let mut plan = find_plan(&id)?;
+  refresh_plan(&mut plan);
+  match plan.status.as_str() {
+    "snapshot_ready" | "failed" => {}
+    "scheduled" => return Err(format!("Wait for block {} and the snapshot first", plan.snapshot_height)),
+    other => return Err(format!("Plan is {}", other)),
+  }
+  match run_cli(&distribute_args(&plan, false)) {
+    Ok(raw) => {
+      plan.status = String::from("distributing");
+      plan.node_status = Some(raw);
+      plan.error = None;
+    }
+    Err(e) => {
+      plan.status = String::from("failed");
+      plan.error = Some(e.clone());
+      update_plan(&plan)?;
+      return Err(e);
+    }
+  }
+  update_plan(&plan)?;
+  drop(guard);
+  spawn_tracker(app_handle);
+  Ok(plan)
+}
+
+/// Cancel a plan. The node's snapshot request is withdrawn while the
+/// snapshot has not been taken yet.
+#[tauri::command]
+pub fn cancel_reward(id: String) -> Result<RewardPlan, String> {
+  ensure_config()?;
+  let _guard = lock_plans()?;
+  let mut plan = find_plan(&id)?;
+  match plan.status.as_str() {
+    "distributing" | "completed" => return Err(format!("Plan is already {}", plan.status)),
+    "scheduled" => {
+      run_cli(&[
+        String::from("cancelsnapshotrequest"),
+        plan.asset_name.clone(),
+        plan.snapshot_height.to_string(),
+      ])?;
+    }
+    _ => {}
+  }
+  plan.status = String::from("cancelled");
+  update_plan(&plan)?;
+  Ok(plan)
+}
+
+/// Remove a finished or cancelled plan from the list
+#[tauri::command]
+pub fn remove_reward(id: String) -> Result<(), String> {
+  let _guard = lock_plans()?;
+  let mut plans = load_reward_plans()?;
+  if let Some(plan) = plans.iter().find(|p| p.id == id) {
+    if plan.status == "scheduled" || plan.status == "distributing" {
+      return Err("Cancel the plan before removing it".to_string());
+    }
+  }
+  plans.retain(|p| p.id != id);
+  save_reward_plans(&plans)
+}
+
+/// Snapshot requests the node has pending, optionally for one asset
+#[tauri::command]
+pub fn list_snapshot_requests(asset_name: Option<String>) -> Result<Vec<SnapshotRequest>, String> {
+  ensure_config()?;
+  let mut args = vec![String::from("listsnapshotrequests")];
+  if let Some(name) = asset_name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()) {
+    args.push(name);
+  }
+  let raw = run_cli(&args)?;
+  let value: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  Ok(value
+    .as_array()
+    .map(|rows| {
+      rows
+        .iter()
+        .map(|r| SnapshotRequest {
+          asset_name: r["asset_name"].as_str().unwrap_or("").to_string(),
+          block_height: r["block_height"].as_u64().unwrap_or(0),
+        })
+        .collect()
+    })
+    .unwrap_or_default())
+}
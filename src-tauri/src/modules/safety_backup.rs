@@ -0,0 +1,158 @@
+use std::fs;
+use std::path::PathBuf;
+use chrono::Local;
+
+// Import local modules
+use crate::modules::models::SafetyBackup;
+use crate::modules::commands::run_cli;
+use crate::modules::files::{data_dir, load_app_settings, wipe_file};
+use crate::modules::process::restore_wallet;
+use crate::modules::wallets::{active_wallet, active_wallet_path};
+
+// Every operation that can lose or rewrite keys first drops a copy of
+// the active wallet into `wallet_backups/safety/<wallet>/`. If the copy
+// cannot be made the operation does not run. Retention counts per wallet
+// and only ever deletes `safety_` files in that folder, never the
+// `wallet_<ts>.bak` copies older versions left in `wallet_backups/` or
+// anything else the user keeps there. Snapshots are wiped rather than just
+// unlinked, since most of them hold private keys.
+
+pub const DEFAULT_SAFETY_BACKUP_KEEP: u32 = 20;
+const SAFETY_PREFIX: &str = "safety_";
+
+/// Snapshot folder of the active wallet
+fn safety_backup_dir() -> Result<PathBuf, String> {
+  let wallet = active_wallet();
+  let dir = data_dir()?
+    .join("wallet_backups")
+    .join("safety")
+    .join(wallet.trim_end_matches(".dat"));
+  fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+  Ok(dir)
+}
+
+fn is_safety_backup(name: &str) -> bool {
+  name.starts_with(SAFETY_PREFIX) && name.ends_with(".bak")
+}
+
+/// `safety_<date>_<time>_<reason>.bak`
+fn reason_from_name(name: &str) -> String {
+  let stem = name.trim_start_matches(SAFETY_PREFIX).trim_end_matches(".bak");
+  stem.splitn(3, '_').nth(2).unwrap_or("manual").to_string()
+}
+
+/// Safety copies, newest first
+fn list_backups() -> Result<Vec<SafetyBackup>, String> {
+  let mut backups = Vec::new();
+  for entry in fs::read_dir(safety_backup_dir()?).map_err(|e| e.to_string())?.flatten() {
+    let name = entry.file_name().to_string_lossy().to_string();
+    if !is_safety_backup(&name) {
+      continue;
+    }
+    let meta = match entry.metadata() {
+      Ok(m) => m,
+      Err(_) => continue,
+    };
+    let created = meta
+      .modified()
+      .ok()
+      .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+      .map(|d| d.as_secs())
+      .unwrap_or(0);
+    backups.push(SafetyBackup {
+      reason: reason_from_name(&name),
+      path: entry.path().to_string_lossy().to_string(),
+      name,
+      created,
+      size: meta.len(),
+    });
+  }
+  backups.sort_by_key(|b| std::cmp::Reverse(b.created));
+  Ok(backups)
+}
+
+/// Drop the active wallet's oldest snapshots past the retention count
+fn prune_safety_backups() -> Result<(), String> {
+  let keep = load_app_settings()
+    .ok()
+    .and_then(|s| s.safety_backup_keep)
+    .unwrap_or(DEFAULT_SAFETY_BACKUP_KEEP)
+    .max(1) as usize;
+  for old in list_backups()?.into_iter().skip(keep) {
+    let _ = wipe_file(std::path::Path::new(&old.path));
+  }
+  Ok(())
+}
+
+/// Copy the active wallet into its snapshot folder before a risky operation. Uses
+/// `backupwallet` while the node runs (consistent copy of an open wallet)
+/// and a plain file copy when it is stopped. Returns `None` when there is
+/// no wallet yet.
+pub fn snapshot_wallet(reason: &str) -> Result<Option<PathBuf>, String> {
+  let wallet = active_wallet_path()?;
+  if !wallet.exists() {
+    return Ok(None);
+  }
+  let ts = Local::now().format("%Y%m%d_%H%M%S").to_string();
+  let mut dest = safety_backup_dir()?.join(format!("{}{}_{}.bak", SAFETY_PREFIX, ts, reason));
+  let mut n = 1;
+  while dest.exists() {
+    dest = safety_backup_dir()?.join(format!("{}{}_{}-{}.bak", SAFETY_PREFIX, ts, reason, n));
+    n += 1;
+  }
+
+  let via_node = run_cli(&[String::from("backupwallet"), dest.to_string_lossy().to_string()]).is_ok();
+  if !via_node || !dest.exists() {
+    fs::copy(&wallet, &dest).map_err(|e| format!("Safety backup of {} failed: {}", wallet.to_string_lossy(), e))?;
+  }
+  if let Err(e) = prune_safety_backups() {
+    log::warn!("Failed to prune safety backups: {}", e);
+  }
+  Ok(Some(dest))
+}
+
+/// Wipe a snapshot once the operation it guarded has succeeded. Used where
+/// keeping it would leave keys behind that the operation meant to protect:
+/// the unencrypted copy taken before `encryptwallet`, or the copy that
+/// still opens with the old password.
+pub fn discard_snapshot(snapshot: Option<PathBuf>) {
+  if let Some(path) = snapshot {
+    if let Err(e) = wipe_file(&path) {
+      log::warn!("Failed to wipe safety backup {}: {}", path.to_string_lossy(), e);
+    }
+  }
+}
+
+#[tauri::command]
+pub fn list_safety_backups() -> Result<Vec<SafetyBackup>, String> {
+  list_backups()
+}
+
+/// Put a safety copy back as the active wallet. The current wallet is itself
+/// copied first, so a restore can always be undone.
+#[tauri::command]
+pub fn restore_safety_backup(name: String, restart_node: bool) -> Result<(), String> {
+  if !is_safety_backup(&name) || name.contains('/') || name.contains('\\') {
+    return Err("Not a safety backup".to_string());
+  }
+  let path = safety_backup_dir()?.join(&name);
+  if !path.exists() {
+    return Err(format!("{} not found", name));
+  }
+  // Restore from a temporary copy: the snapshot taken by restore_wallet may
+  // prune the very backup being restored
+  let temp = data_dir()?.join(format!("restore_{}.tmp", Local::now().format("%Y%m%d_%H%M%S")));
+  fs::copy(&path, &temp).map_err(|e| e.to_string())?;
+  let result = restore_wallet(temp.to_string_lossy().to_string(), restart_node);
+  let _ = fs::remove_file(&temp);
+  result
+}
+
+/// Wipe a safety copy the user no longer wants
+#[tauri::command]
+pub fn delete_safety_backup(name: String) -> Result<(), String> {
+  if !is_safety_backup(&name) || name.contains('/') || name.contains('\\') {
+    return Err("Not a safety backup".to_string());
+  }
+  wipe_file(&safety_backup_dir()?.join(&name))
+}
@@ -0,0 +1,197 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use chrono::Local;
+use rand::seq::index::sample;
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+// Import local modules
+use crate::modules::models::{MasterKeyInfo, SeedBackupRecord, SeedBackupStatus, SeedWordAnswer, SeedWords};
+use crate::modules::commands::{run_cli, with_unlocked_wallet};
+use crate::modules::files::{data_dir, ensure_config};
+
+// A seed counts as backed up only after the user has typed back a few
+// randomly chosen words. Records are keyed by the wallet's HD master key
+// id so a new or restored wallet.dat starts out as "not backed up".
+
+pub const CHALLENGE_WORDS: usize = 3;
+
+struct SeedChallenge {
+  wallet_id: String,
+  fingerprint: String,
+  words: Vec<(usize, String)>,
+}
+
+static PENDING_CHALLENGE: Mutex<Option<SeedChallenge>> = Mutex::new(None);
+
+fn seed_backup_path() -> Result<PathBuf, String> {
+  Ok(data_dir()?.join("seed_backup.json"))
+}
+
+fn load_seed_backups() -> Result<Vec<SeedBackupRecord>, String> {
+  let path = seed_backup_path()?;
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+  let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+  let records: Vec<SeedBackupRecord> = serde_json::from_str(&content).unwrap_or_default();
+  Ok(records)
+}
+
+fn save_seed_backups(records: &[SeedBackupRecord]) -> Result<(), String> {
+  let path = seed_backup_path()?;
+  let content = serde_json::to_string_pretty(records).map_err(|e| e.to_string())?;
+  fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+/// HD master key id from `getwalletinfo`; `None` for non-HD wallets.
+/// Readable while the wallet is locked.
+fn wallet_id() -> Result<Option<String>, String> {
+  let raw = run_cli(&[String::from("getwalletinfo")])?;
+  let info: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  Ok(info["hdmasterkeyid"]
+    .as_str()
+    .or_else(|| info["hdseedid"].as_str())
+    .map(|s| s.to_string()))
+}
+
+/// BIP32 fingerprint: first 4 bytes of HASH160 of the key inside an xpub
+pub fn xpub_fingerprint(xpub: &str) -> Result<String, String> {
+  let data = bs58::decode(xpub.trim())
+    .with_check(None)
+    .into_vec()
+    .map_err(|e| format!("Invalid extended public key: {}", e))?;
+  if data.len() != 78 {
+    return Err("Invalid extended public key length".to_string());
+  }
+  let hash = Ripemd160::digest(Sha256::digest(&data[45..78]));
+  Ok(hash[..4].iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn master_key_info() -> Result<MasterKeyInfo, String> {
+  let raw = run_cli(&[String::from("getmasterkeyinfo")])?;
+  let info: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  let root_public = info["bip32_root_public"]
+    .as_str()
+    .ok_or("Node did not return a master public key")?
+    .to_string();
+  Ok(MasterKeyInfo {
+    fingerprint: xpub_fingerprint(&root_public)?,
+    root_public,
+    derivation_path: info["account_derivation_path"].as_str().map(|s| s.to_string()),
+    account_public: info["account_extended_public_key"].as_str().map(|s| s.to_string()),
+  })
+}
+
+/// Master key fingerprint and public keys (private keys are not returned),
+/// to compare against a restored wallet
+#[tauri::command]
+pub fn get_master_key_info(wallet_passphrase: Option<String>) -> Result<MasterKeyInfo, String> {
+  ensure_config()?;
+  with_unlocked_wallet(wallet_passphrase.as_deref(), master_key_info)
+}
+
+/// Reveal the recovery words and start a confirmation challenge for
+/// `CHALLENGE_WORDS` random positions (1-based)
+#[tauri::command]
+pub fn get_seed_words(wallet_passphrase: Option<String>) -> Result<SeedWords, String> {
+  ensure_config()?;
+  let id = wallet_id()?.ok_or("This wallet is not an HD wallet and has no recovery words")?;
+  let (value, info) = with_unlocked_wallet(wallet_passphrase.as_deref(), || {
+    let raw = run_cli(&[String::from("getmywords")])
+      .map_err(|e| format!("Recovery words unavailable: {}", e))?;
+    let value: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+    Ok((value, master_key_info()?))
+  })?;
+
+  let words: Vec<String> = value["word_list"]
+    .as_str()
+    .ok_or("Node did not return a word list")?
+    .split_whitespace()
+    .map(|w| w.to_string())
+    .collect();
+  let passphrase = value["passphrase"].as_str().filter(|p| !p.is_empty()).map(|p| p.to_string());
+
+  let mut positions = sample(&mut rand::thread_rng(), words.len(), CHALLENGE_WORDS.min(words.len())).into_vec();
+  positions.sort_unstable();
+  let challenge = SeedChallenge {
+    wallet_id: id,
+    fingerprint: info.fingerprint.clone(),
+    words: positions.iter().map(|&i| (i + 1, words[i].clone())).collect(),
+  };
+  *PENDING_CHALLENGE.lock().map_err(|e| e.to_string())? = Some(challenge);
+
+  Ok(SeedWords {
+    words,
+    passphrase,
+    challenge: positions.iter().map(|i| i + 1).collect(),
+    fingerprint: info.fingerprint,
+  })
+}
+
+/// Check the words typed back against the pending challenge and record the
+/// backup on success. A failed attempt keeps the challenge so the user can retry.
+#[tauri::command]
+pub fn confirm_seed_backup(answers: Vec<SeedWordAnswer>) -> Result<SeedBackupStatus, String> {
+  let mut pending = PENDING_CHALLENGE.lock().map_err(|e| e.to_string())?;
+  let challenge = pending.as_ref().ok_or("Show the recovery words before confirming them")?;
+
+  let wrong: Vec<String> = challenge
+    .words
+    .iter()
+    .filter(|(pos, word)| {
+      !answers
+        .iter()
+        .any(|a| a.position == *pos && a.word.trim().eq_ignore_ascii_case(word))
+    })
+    .map(|(pos, _)| format!("#{}", pos))
+    .collect();
+  if !wrong.is_empty() {
+    return Err(format!("Word {} does not match, check your backup", wrong.join(", ")));
+  }
+
+  let record = SeedBackupRecord {
+    wallet_id: challenge.wallet_id.clone(),
+    fingerprint: challenge.fingerprint.clone(),
+    date: Local::now().timestamp() as u64,
+  };
+  *pending = None;
+
+  let mut records = load_seed_backups()?;
+  records.retain(|r| r.wallet_id != record.wallet_id);
+  records.push(record.clone());
+  save_seed_backups(&records)?;
+
+  Ok(SeedBackupStatus {
+    hd: true,
+    backed_up: true,
+    backed_up_at: Some(record.date),
+    fingerprint: Some(record.fingerprint),
+  })
+}
+
+#[tauri::command]
+pub fn get_seed_backup_status() -> Result<SeedBackupStatus, String> {
+  ensure_config()?;
+  let id = match wallet_id()? {
+    Some(id) => id,
+    None => {
+      return Ok(SeedBackupStatus { hd: false, backed_up: false, backed_up_at: None, fingerprint: None });
+    }
+  };
+  let record = load_seed_backups()?.into_iter().find(|r| r.wallet_id == id);
+  Ok(SeedBackupStatus {
+    hd: true,
+    backed_up: record.is_some(),
+    backed_up_at: record.as_ref().map(|r| r.date),
+    fingerprint: record.map(|r| r.fingerprint),
+  })
+}
+
+/// For the dashboard reminder: true while an HD wallet's words are unconfirmed
+pub fn seed_backup_needed() -> bool {
+  get_seed_backup_status()
+    .map(|s| s.hd && !s.backed_up)
+    .unwrap_or(false)
+}
@@ -0,0 +1,188 @@
+use std::fs;
+use std::path::PathBuf;
+use chrono::Local;
+
+// Import local modules
+use crate::modules::models::{HolderHistoryPoint, HolderShare, SnapshotAnalytics};
+use crate::modules::utils::{to_sats, format_sats};
+use crate::modules::commands::run_cli;
+use crate::modules::files::{data_dir, ensure_config};
+use crate::modules::rewards::fetch_snapshot;
+
+// Ownership analytics on top of the node's asset snapshots. Snapshots only
+// exist for heights requested beforehand (`requestsnapshot`); the figures
+// of every snapshot looked at are kept in `snapshot_history.json`, so the
+// holder count over time survives `purgesnapshot`.
+
+pub const DEFAULT_TOP_HOLDERS: usize = 20;
+
+fn history_path() -> Result<PathBuf, String> {
+  Ok(data_dir()?.join("snapshot_history.json"))
+}
+
+fn load_history() -> Result<Vec<HolderHistoryPoint>, String> {
+  let path = history_path()?;
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+  let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+  let points: Vec<HolderHistoryPoint> = serde_json::from_str(&content).unwrap_or_default();
+  Ok(points)
+}
+
+fn save_history(points: &[HolderHistoryPoint]) -> Result<(), String> {
+  let path = history_path()?;
+  let content = serde_json::to_string_pretty(points).map_err(|e| e.to_string())?;
+  fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+fn record_point(point: HolderHistoryPoint) -> Result<(), String> {
+  let mut points = load_history()?;
+  points.retain(|p| !(p.asset_name == point.asset_name && p.height == point.height));
+  points.push(point);
+  save_history(&points)
+}
+
+/// Holders with a balance, largest first (ties by address, so the order is stable)
+fn sorted_holders(asset: &str, height: u64) -> Result<Vec<(String, u64)>, String> {
+  let mut holders: Vec<(String, u64)> = fetch_snapshot(asset, height)?
+    .into_iter()
+    .filter(|(_, amount)| *amount > 0)
+    .collect();
+  holders.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+  Ok(holders)
+}
+
+/// Gini coefficient of the holdings: 0 when everyone holds the same, close
+/// to 1 when one address holds nearly everything
+pub fn gini(amounts: &[u64]) -> f64 {
+  let n = amounts.len();
+  let total: f64 = amounts.iter().map(|a| *a as f64).sum();
+  if n < 2 || total == 0.0 {
+    return 0.0;
+  }
+  let mut sorted = amounts.to_vec();
+  sorted.sort_unstable();
+  let weighted: f64 = sorted.iter().enumerate().map(|(i, a)| (i + 1) as f64 * *a as f64).sum();
+  (2.0 * weighted) / (n as f64 * total) - (n as f64 + 1.0) / n as f64
+}
+
+fn percent(part: u64, total: u64) -> f64 {
+  if total == 0 {
+    0.0
+  } else {
+    part as f64 * 100.0 / total as f64
+  }
+}
+
+fn analyze(asset: &str, height: u64, top: usize) -> Result<SnapshotAnalytics, String> {
+  let holders = sorted_holders(asset, height)?;
+  let amounts: Vec<u64> = holders.iter().map(|(_, a)| *a).collect();
+  let total: u64 = amounts.iter().sum();
+  let gini = gini(&amounts);
+
+  record_point(HolderHistoryPoint {
+    asset_name: asset.to_string(),
+    height,
+    holders: holders.len(),
+    total: format_sats(total),
+    gini,
+    recorded: Local::now().timestamp() as u64,
+  })?;
+
+  let supply = run_cli(&[String::from("getassetdata"), asset.to_string()])
+    .ok()
+    .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+    .and_then(|v| v["amount"].as_f64())
+    .map(|a| format_sats(to_sats(a)));
+
+  Ok(SnapshotAnalytics {
+    asset_name: asset.to_string(),
+    height,
+    holders: holders.len(),
+    total: format_sats(total),
+    supply,
+    top1_percent: percent(amounts.first().copied().unwrap_or(0), total),
+    top10_percent: percent(amounts.iter().take(10).sum(), total),
+    gini,
+    top: holders
+      .into_iter()
+      .take(top)
+      .enumerate()
+      .map(|(i, (address, amount))| HolderShare {
+        rank: i + 1,
+        percent: percent(amount, total),
+        amount: format_sats(amount),
+        address,
+      })
+      .collect(),
+  })
+}
+
+/// Ask the node to snapshot the holders of `asset_name` at `block_height`
+#[tauri::command]
+pub fn request_holder_snapshot(asset_name: String, block_height: u64) -> Result<(), String> {
+  ensure_config()?;
+  run_cli(&[String::from("requestsnapshot"), asset_name.trim().to_string(), block_height.to_string()])?;
+  Ok(())
+}
+
+/// Top holders and concentration figures of a snapshot
+#[tauri::command]
+pub fn analyze_snapshot(asset_name: String, block_height: u64, top: Option<usize>) -> Result<SnapshotAnalytics, String> {
+  ensure_config()?;
+  analyze(asset_name.trim(), block_height, top.unwrap_or(DEFAULT_TOP_HOLDERS))
+}
+
+/// Holder count per snapshot height, oldest first. Snapshots the node has
+/// taken but that were never looked at are analysed on the way.
+#[tauri::command]
+pub fn get_holder_history(asset_name: String) -> Result<Vec<HolderHistoryPoint>, String> {
+  ensure_config()?;
+  let asset = asset_name.trim().to_string();
+  let known: Vec<u64> = load_history()?
+    .iter()
+    .filter(|p| p.asset_name == asset)
+    .map(|p| p.height)
+    .collect();
+  let current: u64 = run_cli(&[String::from("getblockcount")])
+    .ok()
+    .and_then(|h| h.trim().parse().ok())
+    .unwrap_or(0);
+  if let Ok(raw) = run_cli(&[String::from("listsnapshotrequests"), asset.clone()]) {
+    let requests: serde_json::Value = serde_json::from_str(&raw).unwrap_or_default();
+    for height in requests.as_array().into_iter().flatten().filter_map(|r| r["block_height"].as_u64()) {
+      if height <= current && !known.contains(&height) {
+        let _ = analyze(&asset, height, 0);
+      }
+    }
+  }
+  let mut points: Vec<HolderHistoryPoint> = load_history()?
+    .into_iter()
+    .filter(|p| p.asset_name == asset)
+    .collect();
+  points.sort_by_key(|p| p.height);
+  Ok(points)
+}
+
+/// Write all holders of a snapshot as CSV: rank, address, amount, percent
+#[tauri::command]
+pub fn export_snapshot_csv(asset_name: String, block_height: u64, path: String) -> Result<usize, String> {
+  ensure_config()?;
+  let holders = sorted_holders(asset_name.trim(), block_height)?;
+  let total: u64 = holders.iter().map(|(_, a)| *a).sum();
+  let mut csv = String::from("rank,address,amount,percent\n");
+  for (i, (address, amount)) in holders.iter().enumerate() {
+    csv.push_str(&format!("{},{},{},{:.6}\n", i + 1, address, format_sats(*amount), percent(*amount, total)));
+  }
+  fs::write(&path, csv).map_err(|e| e.to_string())?;
+  Ok(holders.len())
+}
+
+/// Drop the node's copy of a snapshot. Its figures stay in the history.
+#[tauri::command]
+pub fn purge_snapshot(asset_name: String, block_height: u64) -> Result<(), String> {
+  ensure_config()?;
+  run_cli(&[String::from("purgesnapshot"), asset_name.trim().to_string(), block_height.to_string()])?;
+  Ok(())
+}
@@ -0,0 +1,184 @@
+use chrono::Local;
+
+// Import local modules
+use crate::modules::models::{BatchTransferResult, TransferRecipient, TransferResult};
+use crate::modules::utils::{to_sats, parse_amount_sats, format_sats, txid_from};
+use crate::modules::commands::run_cli;
+use crate::modules::files::ensure_config;
+use crate::modules::naming::onchain_ipfs_hash;
+
+// Asset transfers with everything the node's `transfer` family accepts: a
+// memo (IPFS hash or txid) with optional expiry, separate HEMP and asset
+// change addresses, and the addresses to spend from.
+
+// Unconfirmed transactions the mempool lets one chain hold; a batch sends
+// each transfer from the change of the previous one
+pub const MAX_BATCH_RECIPIENTS: usize = 24;
+
+/// Everything besides asset, amount and recipient
+#[derive(Default)]
+pub struct TransferOptions {
+  pub memo: String,
+  pub expire_time: u64,
+  pub change_address: String,
+  pub asset_change_address: String,
+  pub from_addresses: Vec<String>,
+}
+
+impl TransferOptions {
+  fn is_plain(&self) -> bool {
+    self.memo.is_empty()
+      && self.expire_time == 0
+      && self.change_address.is_empty()
+      && self.asset_change_address.is_empty()
+      && self.from_addresses.is_empty()
+  }
+}
+
+fn optional(value: Option<String>) -> String {
+  value.map(|v| v.trim().to_string()).unwrap_or_default()
+}
+
+pub fn build_options(
+  memo: Option<String>,
+  expire_time: Option<u64>,
+  change_address: Option<String>,
+  asset_change_address: Option<String>,
+  from_addresses: Option<Vec<String>>,
+) -> Result<TransferOptions, String> {
+  let memo = onchain_ipfs_hash(&optional(memo)).map_err(|e| format!("Memo: {}", e))?;
+  let options = TransferOptions {
+    memo,
+    expire_time: expire_time.unwrap_or(0),
+    change_address: optional(change_address),
+    asset_change_address: optional(asset_change_address),
+    from_addresses: from_addresses
+      .unwrap_or_default()
+      .into_iter()
+      .map(|a| a.trim().to_string())
+      .filter(|a| !a.is_empty())
+      .collect(),
+  };
+  if options.expire_time > 0 {
+    if options.memo.is_empty() {
+      return Err("An expiry time only applies to a memo".to_string());
+    }
+    if options.expire_time <= Local::now().timestamp() as u64 {
+      return Err("Memo expiry time must be in the future".to_string());
+    }
+  }
+  Ok(options)
+}
+
+/// Send `qty` of `asset` to `to`. Without options this is a plain
+/// `transfer`; source addresses switch to `transferfromaddress(es)`.
+pub fn send_asset(asset: &str, qty: &str, to: &str, options: &TransferOptions) -> Result<String, String> {
+  if options.is_plain() {
+    return Ok(txid_from(&run_cli(&[
+      String::from("transfer"),
+      asset.to_string(),
+      qty.to_string(),
+      to.to_string(),
+    ])?));
+  }
+  let mut args = match options.from_addresses.len() {
+    0 => vec![String::from("transfer"), asset.to_string()],
+    1 => vec![String::from("transferfromaddress"), asset.to_string(), options.from_addresses[0].clone()],
+    _ => vec![
+      String::from("transferfromaddresses"),
+      asset.to_string(),
+      serde_json::to_string(&options.from_addresses).map_err(|e| e.to_string())?,
+    ],
+  };
+  args.extend([
+    qty.to_string(),
+    to.to_string(),
+    options.memo.clone(),
+    options.expire_time.to_string(),
+    options.change_address.clone(),
+    options.asset_change_address.clone(),
+  ]);
+  Ok(txid_from(&run_cli(&args)?))
+}
+
+fn asset_balance(asset: &str) -> Result<u64, String> {
+  let raw = run_cli(&[String::from("listmyassets"), asset.to_string()])?;
+  let value: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  Ok(to_sats(value.get(asset).and_then(|v| v.as_f64()).unwrap_or(0.0)))
+}
+
+fn is_valid_address(address: &str) -> bool {
+  run_cli(&[String::from("validateaddress"), address.to_string()])
+    .ok()
+    .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+    .and_then(|v| v["isvalid"].as_bool())
+    .unwrap_or(false)
+}
+
+/// Send one asset to many recipients, one transfer each. Every recipient
+/// is checked before anything is sent; a failed transfer is recorded and
+/// the batch goes on. Source addresses are refused: the first transfer
+/// moves their asset change elsewhere, so the later ones would find
+/// nothing left to spend.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn batch_transfer_asset(
+  asset: String,
+  recipients: Vec<TransferRecipient>,
+  memo: Option<String>,
+  expire_time: Option<u64>,
+  change_address: Option<String>,
+  asset_change_address: Option<String>,
+  from_addresses: Option<Vec<String>>,
+) -> Result<BatchTransferResult, String> {
+  ensure_config()?;
+  if recipients.is_empty() {
+    return Err("No recipients given".to_string());
+  }
+  if recipients.len() > MAX_BATCH_RECIPIENTS {
+    return Err(format!(
+      "At most {} recipients per batch; the rest would exceed the unconfirmed chain limit",
+      MAX_BATCH_RECIPIENTS
+    ));
+  }
+  let options = build_options(memo, expire_time, change_address, asset_change_address, from_addresses)?;
+  if !options.from_addresses.is_empty() {
+    return Err("Source addresses can only be used for a single transfer, not a batch".to_string());
+  }
+
+  let mut planned = Vec::new();
+  let mut total = 0u64;
+  for (i, r) in recipients.iter().enumerate() {
+    let address = r.address.trim().to_string();
+    if !is_valid_address(&address) {
+      return Err(format!("Recipient {}: invalid address {}", i + 1, address));
+    }
+    let sats = parse_amount_sats(&r.amount).map_err(|e| format!("Recipient {}: {}", i + 1, e))?;
+    if sats == 0 {
+      return Err(format!("Recipient {}: amount must be greater than zero", i + 1));
+    }
+    total += sats;
+    planned.push((address, sats));
+  }
+  let balance = asset_balance(&asset)?;
+  if total > balance {
+    return Err(format!("Batch needs {} {} but the wallet holds {}", format_sats(total), asset, format_sats(balance)));
+  }
+
+  let mut results = Vec::new();
+  for (address, sats) in planned {
+    let amount = format_sats(sats);
+    results.push(match send_asset(&asset, &amount, &address, &options) {
+      Ok(txid) => TransferResult { address, amount, txid: Some(txid), error: None },
+      Err(e) => TransferResult { address, amount, txid: None, error: Some(e) },
+    });
+  }
+  let sent = results.iter().filter(|r| r.error.is_none()).count() as u32;
+  Ok(BatchTransferResult {
+    asset,
+    total: format_sats(total),
+    failed: results.len() as u32 - sent,
+    sent,
+    results,
+  })
+}
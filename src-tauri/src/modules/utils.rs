@@ -194,3 +194,44 @@ pub fn version_is_old(subver: &str) -> bool {
   }
   true
 }
+
+pub const COIN: u64 = 100_000_000;
+
+pub fn to_sats(amount: f64) -> u64 {
+  if amount <= 0.0 {
+    return 0;
+  }
+  (amount * COIN as f64).round() as u64
+}
+
+pub fn parse_amount_sats(value: &str) -> Result<u64, String> {
+  let amount: f64 = value
+    .trim()
+    .parse()
+    .map_err(|_| format!("Invalid amount: {}", value))?;
+  if !amount.is_finite() || amount < 0.0 {
+    return Err(format!("Invalid amount: {}", value));
+  }
+  Ok(to_sats(amount))
+}
+
+pub fn format_sats(sats: u64) -> String {
+  format!("{}.{:08}", sats / COIN, sats % COIN)
+}
+
+/// Asset quantity as the exact decimal string the node expects
+pub fn parse_asset_qty(value: &str) -> Result<String, String> {
+  let sats = parse_amount_sats(value).map_err(|_| "Quantity must be a number".to_string())?;
+  if sats == 0 {
+    return Err("Quantity must be greater than zero".to_string());
+  }
+  Ok(format_sats(sats))
+}
+
+/// First txid of an asset RPC result, which comes back as `["txid"]`
+pub fn txid_from(raw: &str) -> String {
+  serde_json::from_str::<Vec<String>>(raw)
+    .ok()
+    .and_then(|ids| ids.into_iter().next())
+    .unwrap_or_else(|| raw.trim().to_string())
+}
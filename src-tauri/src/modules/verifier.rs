@@ -0,0 +1,401 @@
+use std::fmt;
+
+// Import local modules
+use crate::modules::models::{VerifierCheck, VerifierEvaluation};
+use crate::modules::commands::run_cli;
+use crate::modules::files::ensure_config;
+use crate::modules::qualifiers::tags_for_address;
+
+// Verifier strings decide who may hold a restricted asset, e.g.
+// `#KYC & !#BANNED` or `(#KYC | #ACCREDITED) & !#US`. `!` binds tightest,
+// then `&`, then `|`. The node stores them without spaces and `#`
+// (`KYC&!BANNED`), so both spellings are accepted here.
+
+/// Length limit the node applies to the stored (stripped) form
+pub const MAX_VERIFIER_LENGTH: usize = 80;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum VerifierExpr {
+  True,
+  Tag(String),
+  Not(Box<VerifierExpr>),
+  And(Box<VerifierExpr>, Box<VerifierExpr>),
+  Or(Box<VerifierExpr>, Box<VerifierExpr>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+  True,
+  Tag(String),
+  Not,
+  And,
+  Or,
+  Open,
+  Close,
+}
+
+impl Token {
+  fn describe(&self) -> String {
+    match self {
+      Token::True => "\"true\"".to_string(),
+      Token::Tag(name) => name.clone(),
+      Token::Not => "'!'".to_string(),
+      Token::And => "'&'".to_string(),
+      Token::Or => "'|'".to_string(),
+      Token::Open => "'('".to_string(),
+      Token::Close => "')'".to_string(),
+    }
+  }
+}
+
+fn is_name_char(c: char) -> bool {
+  c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_' || c == '.' || c == '/' || c == '#'
+}
+
+/// Tokens with their 1-based character position
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, String> {
+  let chars: Vec<char> = input.chars().collect();
+  let mut tokens = Vec::new();
+  let mut i = 0;
+  while i < chars.len() {
+    let c = chars[i];
+    let pos = i + 1;
+    match c {
+      _ if c.is_whitespace() => {}
+      '!' => tokens.push((Token::Not, pos)),
+      '(' => tokens.push((Token::Open, pos)),
+      ')' => tokens.push((Token::Close, pos)),
+      '&' | '|' => {
+        if chars.get(i + 1) == Some(&c) {
+          return Err(format!("Use a single '{}' at position {}, '{}{}' is not an operator", c, pos, c, c));
+        }
+        tokens.push((if c == '&' { Token::And } else { Token::Or }, pos));
+      }
+      _ if c.is_ascii_alphanumeric() || c == '#' || c == '_' => {
+        let start = i;
+        while i + 1 < chars.len() && (chars[i + 1].is_ascii_alphanumeric() || is_name_char(chars[i + 1])) {
+          i += 1;
+        }
+        let word: String = chars[start..=i].iter().collect();
+        if word == "true" {
+          tokens.push((Token::True, pos));
+        } else {
+          tokens.push((Token::Tag(tag_token(&word, pos)?), pos));
+        }
+      }
+      _ => {
+        return Err(format!(
+          "Unexpected character '{}' at position {}. Use qualifiers such as #KYC with '&', '|', '!' and parentheses",
+          c, pos
+        ))
+      }
+    }
+    i += 1;
+  }
+  Ok(tokens)
+}
+
+/// Qualifier name as `#NAME` (sub-qualifiers as `#NAME/#SUB`)
+fn tag_token(word: &str, pos: usize) -> Result<String, String> {
+  let parts: Vec<&str> = word.split('/').map(|p| p.trim_start_matches('#')).collect();
+  if parts.iter().any(|p| p.is_empty()) {
+    return Err(format!("Incomplete qualifier name '{}' at position {}", word, pos));
+  }
+  if parts.iter().any(|p| p.contains('#')) {
+    return Err(format!("'#' can only start a qualifier name: '{}' at position {}", word, pos));
+  }
+  if word.chars().any(|c| c.is_ascii_lowercase()) {
+    return Err(format!(
+      "Qualifier names are upper case: '{}' at position {} should be '#{}'",
+      word,
+      pos,
+      parts.join("/#").to_ascii_uppercase()
+    ));
+  }
+  Ok(format!("#{}", parts.join("/#")))
+}
+
+struct Parser {
+  tokens: Vec<(Token, usize)>,
+  pos: usize,
+  /// Character count of the input, for errors at the end
+  len: usize,
+}
+
+impl Parser {
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.pos).map(|(t, _)| t)
+  }
+
+  fn next(&mut self) -> Option<(Token, usize)> {
+    let token = self.tokens.get(self.pos).cloned();
+    self.pos += 1;
+    token
+  }
+
+  fn or(&mut self) -> Result<VerifierExpr, String> {
+    let mut left = self.and()?;
+    while self.peek() == Some(&Token::Or) {
+      self.pos += 1;
+      left = VerifierExpr::Or(Box::new(left), Box::new(self.and()?));
+    }
+    Ok(left)
+  }
+
+  fn and(&mut self) -> Result<VerifierExpr, String> {
+    let mut left = self.unary()?;
+    while self.peek() == Some(&Token::And) {
+      self.pos += 1;
+      left = VerifierExpr::And(Box::new(left), Box::new(self.unary()?));
+    }
+    Ok(left)
+  }
+
+  fn unary(&mut self) -> Result<VerifierExpr, String> {
+    if self.peek() == Some(&Token::Not) {
+      self.pos += 1;
+      return Ok(VerifierExpr::Not(Box::new(self.unary()?)));
+    }
+    self.primary()
+  }
+
+  fn primary(&mut self) -> Result<VerifierExpr, String> {
+    let after = match self.pos.checked_sub(1).and_then(|p| self.tokens.get(p)) {
+      Some((t, _)) => format!(" after {}", t.describe()),
+      None => String::new(),
+    };
+    match self.next() {
+      Some((Token::Tag(name), _)) => Ok(VerifierExpr::Tag(name)),
+      Some((Token::True, _)) => Ok(VerifierExpr::True),
+      Some((Token::Open, open_pos)) => {
+        if self.peek() == Some(&Token::Close) {
+          return Err(format!("Empty parentheses at position {}", open_pos));
+        }
+        let inner = self.or()?;
+        match self.next() {
+          Some((Token::Close, _)) => Ok(inner),
+          Some((t, p)) => Err(format!("Expected ')' to close the '(' at position {}, found {} at position {}", open_pos, t.describe(), p)),
+          None => Err(format!("The '(' at position {} is never closed", open_pos)),
+        }
+      }
+      Some((t, p)) => Err(format!("Expected a qualifier, '!' or '('{} at position {}, found {}", after, p, t.describe())),
+      None => Err(format!("Expected a qualifier{} at the end (position {})", after, self.len + 1)),
+    }
+  }
+}
+
+fn references_true(expr: &VerifierExpr) -> bool {
+  match expr {
+    VerifierExpr::True => true,
+    VerifierExpr::Tag(_) => false,
+    VerifierExpr::Not(e) => references_true(e),
+    VerifierExpr::And(a, b) | VerifierExpr::Or(a, b) => references_true(a) || references_true(b),
+  }
+}
+
+/// Parse a verifier string, with an explanation of what is wrong if it
+/// cannot be parsed
+pub fn parse_verifier(input: &str) -> Result<VerifierExpr, String> {
+  if input.trim().is_empty() {
+    return Err("The verifier string is empty. Use \"true\" to let every address hold the asset".to_string());
+  }
+  let mut parser = Parser { tokens: tokenize(input)?, pos: 0, len: input.chars().count() };
+  let expr = parser.or()?;
+  if let Some((token, pos)) = parser.tokens.get(parser.pos) {
+    let hint = match token {
+      Token::Close => String::new(),
+      _ => ". Join terms with '&' or '|'".to_string(),
+    };
+    return Err(format!("Unexpected {} at position {}{}", token.describe(), pos, hint));
+  }
+  if expr != VerifierExpr::True && references_true(&expr) {
+    return Err("\"true\" can only be used on its own".to_string());
+  }
+  let stripped = stripped_form(&expr);
+  if stripped.len() > MAX_VERIFIER_LENGTH {
+    return Err(format!(
+      "The verifier is {} characters long once spaces and '#' are removed; the limit is {}",
+      stripped.len(),
+      MAX_VERIFIER_LENGTH
+    ));
+  }
+  Ok(expr)
+}
+
+/// Binding strength, for deciding where parentheses are needed
+fn precedence(expr: &VerifierExpr) -> u8 {
+  match expr {
+    VerifierExpr::Or(_, _) => 1,
+    VerifierExpr::And(_, _) => 2,
+    _ => 3,
+  }
+}
+
+impl fmt::Display for VerifierExpr {
+  /// Readable form: `#KYC & !#BANNED`, parentheses only where needed
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let wrap = |f: &mut fmt::Formatter<'_>, e: &VerifierExpr, min: u8| {
+      if precedence(e) < min {
+        write!(f, "({})", e)
+      } else {
+        write!(f, "{}", e)
+      }
+    };
+    match self {
+      VerifierExpr::True => write!(f, "true"),
+      VerifierExpr::Tag(name) => write!(f, "{}", name),
+      VerifierExpr::Not(e) => {
+        write!(f, "!")?;
+        wrap(f, e, 3)
+      }
+      VerifierExpr::And(a, b) => {
+        wrap(f, a, 2)?;
+        write!(f, " & ")?;
+        wrap(f, b, 3)
+      }
+      VerifierExpr::Or(a, b) => {
+        wrap(f, a, 1)?;
+        write!(f, " | ")?;
+        wrap(f, b, 2)
+      }
+    }
+  }
+}
+
+/// The form the node stores: no spaces, no `#`
+pub fn stripped_form(expr: &VerifierExpr) -> String {
+  expr.to_string().chars().filter(|c| !c.is_whitespace() && *c != '#').collect()
+}
+
+/// Qualifiers used in the expression, each once
+pub fn qualifiers_in(expr: &VerifierExpr) -> Vec<String> {
+  fn walk(expr: &VerifierExpr, out: &mut Vec<String>) {
+    match expr {
+      VerifierExpr::True => {}
+      VerifierExpr::Tag(name) => {
+        if !out.contains(name) {
+          out.push(name.clone());
+        }
+      }
+      VerifierExpr::Not(e) => walk(e, out),
+      VerifierExpr::And(a, b) | VerifierExpr::Or(a, b) => {
+        walk(a, out);
+        walk(b, out);
+      }
+    }
+  }
+  let mut out = Vec::new();
+  walk(expr, &mut out);
+  out
+}
+
+fn has_tag(tags: &[String], name: &str) -> bool {
+  tags.iter().any(|t| format!("#{}", t.trim_start_matches('#')) == name)
+}
+
+/// Whether an address with `tags` satisfies the expression
+pub fn evaluate(expr: &VerifierExpr, tags: &[String]) -> bool {
+  match expr {
+    VerifierExpr::True => true,
+    VerifierExpr::Tag(name) => has_tag(tags, name),
+    VerifierExpr::Not(e) => !evaluate(e, tags),
+    VerifierExpr::And(a, b) => evaluate(a, tags) && evaluate(b, tags),
+    VerifierExpr::Or(a, b) => evaluate(a, tags) || evaluate(b, tags),
+  }
+}
+
+/// Qualifiers the address lacks although the expression asks for them
+/// (`missing`) and ones it has although the expression rules them out
+/// (`forbidden`)
+fn explain(expr: &VerifierExpr, tags: &[String], negated: bool, missing: &mut Vec<String>, forbidden: &mut Vec<String>) {
+  match expr {
+    VerifierExpr::True => {}
+    VerifierExpr::Tag(name) => {
+      let present = has_tag(tags, name);
+      if !negated && !present && !missing.contains(name) {
+        missing.push(name.clone());
+      }
+      if negated && present && !forbidden.contains(name) {
+        forbidden.push(name.clone());
+      }
+    }
+    VerifierExpr::Not(e) => explain(e, tags, !negated, missing, forbidden),
+    VerifierExpr::And(a, b) | VerifierExpr::Or(a, b) => {
+      explain(a, tags, negated, missing, forbidden);
+      explain(b, tags, negated, missing, forbidden);
+    }
+  }
+}
+
+/// The node's opinion, which also knows whether the qualifiers exist.
+/// `None` when the node cannot be asked.
+fn node_check(verifier: &str) -> Option<Result<(), String>> {
+  match run_cli(&[String::from("isvalidverifierstring"), verifier.to_string()]) {
+    Ok(_) => Some(Ok(())),
+    Err(e) if e.contains("error code") || e.contains("error message") => {
+      // The node's reason follows "error message:"
+      let reason = e.rsplit("error message:").next().unwrap_or(&e).trim().to_string();
+      Some(Err(reason))
+    }
+    Err(_) => None,
+  }
+}
+
+/// Evaluate `verifier` for `address` using its tags from the node
+pub fn evaluate_for_address(verifier: &str, address: &str) -> Result<VerifierEvaluation, String> {
+  let expr = parse_verifier(verifier)?;
+  let tags = tags_for_address(address)?;
+  let satisfied = evaluate(&expr, &tags);
+  let mut missing = Vec::new();
+  let mut forbidden = Vec::new();
+  if !satisfied {
+    explain(&expr, &tags, false, &mut missing, &mut forbidden);
+  }
+  Ok(VerifierEvaluation {
+    address: address.to_string(),
+    verifier: expr.to_string(),
+    tags,
+    satisfied,
+    missing,
+    forbidden,
+  })
+}
+
+/// Syntax check locally, then ask the node (which also checks that the
+/// qualifiers exist)
+#[tauri::command]
+pub fn check_verifier_string(verifier: String) -> Result<VerifierCheck, String> {
+  ensure_config()?;
+  let expr = match parse_verifier(&verifier) {
+    Ok(expr) => expr,
+    Err(e) => {
+      return Ok(VerifierCheck {
+        verifier,
+        valid: false,
+        normalized: None,
+        stripped: None,
+        qualifiers: Vec::new(),
+        error: Some(e),
+        node_valid: None,
+        node_error: None,
+      })
+    }
+  };
+  let node = node_check(&expr.to_string());
+  Ok(VerifierCheck {
+    valid: node.as_ref().map(|r| r.is_ok()).unwrap_or(true),
+    normalized: Some(expr.to_string()),
+    stripped: Some(stripped_form(&expr)),
+    qualifiers: qualifiers_in(&expr),
+    error: None,
+    node_valid: node.as_ref().map(|r| r.is_ok()),
+    node_error: node.and_then(|r| r.err()),
+    verifier,
+  })
+}
+
+#[tauri::command]
+pub fn evaluate_verifier(verifier: String, address: String) -> Result<VerifierEvaluation, String> {
+  ensure_config()?;
+  evaluate_for_address(&verifier, address.trim())
+}
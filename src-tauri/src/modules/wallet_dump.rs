@@ -0,0 +1,253 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use age::secrecy::SecretString;
+use chrono::Local;
+use tauri::Emitter;
+
+// Import local modules
+use crate::modules::models::{WalletDumpResult, WalletImportProgress};
+use crate::modules::commands::{run_cli, wallet_unlocked, with_unlocked_wallet, with_unlocked_wallet_for};
+use crate::modules::files::{data_dir, ensure_config, wipe_file, write_private_file};
+use crate::modules::rescan::{log_offset, rescan_progress_since};
+
+// `dumpwallet` writes every private key in plain text. When the user asks
+// for an encrypted dump the plain file only lives in the data dir long
+// enough to be encrypted (age, passphrase mode) and is then wiped.
+
+const AGE_HEADER: &[u8] = b"age-encryption.org/v1";
+
+// `importwallet` needs the wallet unlocked through the whole rescan, which
+// can take hours. The unlock is kept short and renewed while the import
+// runs, so a crash leaves the wallet unlocked for minutes at most.
+const IMPORT_UNLOCK_SECS: u64 = 120;
+const IMPORT_RENEW_SECS: u64 = 60;
+
+const PLAINTEXT_WARNING: &str = "The dump holds every private key in plain text. Anyone who can read the file can spend the wallet's coins. Keep it offline and delete it when no longer needed.";
+
+static IMPORT_RUNNING: AtomicBool = AtomicBool::new(false);
+
+fn temp_dump_path(prefix: &str) -> Result<PathBuf, String> {
+  let ts = Local::now().format("%Y%m%d_%H%M%S").to_string();
+  Ok(data_dir()?.join(format!("{}_{}.tmp", prefix, ts)))
+}
+
+fn count_keys(dump: &[u8]) -> u64 {
+  String::from_utf8_lossy(dump)
+    .lines()
+    .filter(|l| !l.trim().is_empty() && !l.starts_with('#'))
+    .count() as u64
+}
+
+fn encrypt_bytes(plain: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+  let encryptor = age::Encryptor::with_user_passphrase(SecretString::from(passphrase.to_string()));
+  let mut encrypted = Vec::new();
+  let mut writer = encryptor.wrap_output(&mut encrypted).map_err(|e| e.to_string())?;
+  writer.write_all(plain).map_err(|e| e.to_string())?;
+  writer.finish().map_err(|e| e.to_string())?;
+  Ok(encrypted)
+}
+
+fn decrypt_bytes(encrypted: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+  let decryptor = age::Decryptor::new(encrypted).map_err(|e| format!("Not a valid encrypted dump: {}", e))?;
+  let identity = age::scrypt::Identity::new(SecretString::from(passphrase.to_string()));
+  let mut reader = decryptor
+    .decrypt(iter::once(&identity as &dyn age::Identity))
+    .map_err(|_| "Wrong passphrase for the encrypted dump".to_string())?;
+  let mut plain = Vec::new();
+  reader.read_to_end(&mut plain).map_err(|e| e.to_string())?;
+  Ok(plain)
+}
+
+pub fn is_encrypted_file(path: &Path) -> bool {
+  let mut header = [0u8; 21];
+  fs::File::open(path)
+    .and_then(|mut f| f.read_exact(&mut header))
+    .map(|_| header == AGE_HEADER)
+    .unwrap_or(false)
+}
+
+/// Export every wallet key with `dumpwallet`. A locked wallet is unlocked
+/// for the dump with `wallet_passphrase` and locked again afterwards. With
+/// `encrypt_passphrase` the file written to `path` is age-encrypted and
+/// gets an `.age` extension. A plain export is refused with a warning until
+/// the caller confirms it with `acknowledge_plaintext`.
+#[tauri::command]
+pub fn export_wallet_dump(
+  path: String,
+  wallet_passphrase: Option<String>,
+  encrypt_passphrase: Option<String>,
+  acknowledge_plaintext: Option<bool>,
+) -> Result<WalletDumpResult, String> {
+  ensure_config()?;
+  let mut dest = PathBuf::from(path.trim());
+  if dest.as_os_str().is_empty() {
+    return Err("Destination path is required".to_string());
+  }
+  let encrypt_passphrase = encrypt_passphrase.filter(|p| !p.is_empty());
+  if encrypt_passphrase.is_none() && !acknowledge_plaintext.unwrap_or(false) {
+    return Err(format!("{} Set an encryption passphrase, or confirm the plain text export.", PLAINTEXT_WARNING));
+  }
+  if encrypt_passphrase.is_some() && dest.extension().and_then(|e| e.to_str()) != Some("age") {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".age");
+    dest.set_file_name(name);
+  }
+  if dest.exists() {
+    return Err(format!("{} already exists", dest.display()));
+  }
+
+  // The node refuses to overwrite files, so the plain dump always goes to a fresh path
+  let plain_path = match &encrypt_passphrase {
+    Some(_) => temp_dump_path("wallet_dump")?,
+    None => dest.clone(),
+  };
+  with_unlocked_wallet(wallet_passphrase.as_deref(), || {
+    run_cli(&[String::from("dumpwallet"), plain_path.to_string_lossy().to_string()])
+  })?;
+
+  let keys = match &encrypt_passphrase {
+    Some(pass) => {
+      let result = fs::read(&plain_path).map_err(|e| e.to_string()).and_then(|plain| {
+        let encrypted = encrypt_bytes(&plain, pass)?;
+        fs::write(&dest, encrypted).map_err(|e| e.to_string())?;
+        Ok(count_keys(&plain))
+      });
+      wipe_file(&plain_path)?;
+      result?
+    }
+    None => fs::read(&dest).map(|d| count_keys(&d)).unwrap_or(0),
+  };
+
+  Ok(WalletDumpResult {
+    path: dest.to_string_lossy().to_string(),
+    encrypted: encrypt_passphrase.is_some(),
+    keys,
+    warning: encrypt_passphrase.is_none().then(|| PLAINTEXT_WARNING.to_string()),
+  })
+}
+
+/// Import a dump made by `dumpwallet` (plain or age-encrypted) on a
+/// background thread. `importwallet` rescans the chain, which can take a
+/// long time, so progress is read from debug.log and reported through
+/// `wallet-import-progress` events.
+#[tauri::command]
+pub fn import_wallet_dump(
+  app_handle: tauri::AppHandle,
+  path: String,
+  wallet_passphrase: Option<String>,
+  decrypt_passphrase: Option<String>,
+) -> Result<(), String> {
+  ensure_config()?;
+  let src = PathBuf::from(path.trim());
+  if !src.exists() {
+    return Err(format!("{} not found", src.display()));
+  }
+
+  if wallet_unlocked()? == Some(false) && wallet_passphrase.as_deref().unwrap_or("").is_empty() {
+    return Err("Wallet is locked. Enter the wallet password to import keys.".to_string());
+  }
+
+  if IMPORT_RUNNING.swap(true, Ordering::SeqCst) {
+    return Err("A wallet import is already running".to_string());
+  }
+
+  // Decrypt before going to the background so a wrong passphrase fails right away
+  let prepared = if is_encrypted_file(&src) {
+    decrypt_passphrase
+      .filter(|p| !p.is_empty())
+      .ok_or_else(|| "This dump is encrypted. Enter its passphrase to import it.".to_string())
+      .and_then(|pass| {
+        let encrypted = fs::read(&src).map_err(|e| e.to_string())?;
+        let plain = decrypt_bytes(&encrypted, &pass)?;
+        let temp_path = temp_dump_path("wallet_import")?;
+        write_private_file(&temp_path, &plain)?;
+        Ok((temp_path, true))
+      })
+  } else {
+    Ok((src, false))
+  };
+  let (import_path, temp) = match prepared {
+    Ok(p) => p,
+    Err(e) => {
+      IMPORT_RUNNING.store(false, Ordering::SeqCst);
+      return Err(e);
+    }
+  };
+
+  let offset = log_offset();
+  thread::spawn(move || {
+    let emit = |stage: &str, progress: Option<f64>, height: Option<u64>, done: bool, error: Option<String>| {
+      let _ = app_handle.emit("wallet-import-progress", WalletImportProgress {
+        stage: stage.to_string(),
+        progress,
+        height,
+        done,
+        error,
+      });
+    };
+    emit("importing", None, None, false, None);
+
+    let file = import_path.to_string_lossy().to_string();
+    let result = with_unlocked_wallet_for(wallet_passphrase.as_deref(), IMPORT_UNLOCK_SECS, |renew_pass| {
+      let (tx, rx) = mpsc::channel();
+      thread::spawn(move || {
+        let _ = tx.send(run_cli(&[String::from("importwallet"), file]));
+      });
+
+      // The node may hold the wallet lock during the rescan, so renewals run
+      // on their own thread and are waited for before the wallet is locked
+      let mut renewal: Option<thread::JoinHandle<()>> = None;
+      let mut renewed_at = Instant::now();
+      let result = loop {
+        match rx.recv_timeout(Duration::from_secs(2)) {
+          Ok(result) => break result,
+          Err(mpsc::RecvTimeoutError::Timeout) => {
+            if let Some((height, progress)) = rescan_progress_since(offset) {
+              emit("rescanning", Some(progress), Some(height), false, None);
+            }
+            let busy = renewal.as_ref().is_some_and(|h| !h.is_finished());
+            if let (Some(pass), false) = (renew_pass, busy) {
+              if renewed_at.elapsed() >= Duration::from_secs(IMPORT_RENEW_SECS) {
+                renewed_at = Instant::now();
+                let pass = pass.to_string();
+                renewal = Some(thread::spawn(move || {
+                  let renewed = run_cli(&[
+                    String::from("walletpassphrase"),
+                    pass,
+                    IMPORT_UNLOCK_SECS.to_string(),
+                  ]);
+                  if let Err(e) = renewed {
+                    log::warn!("Could not renew the unlock during wallet import: {}", e);
+                  }
+                }));
+              }
+            }
+          }
+          Err(mpsc::RecvTimeoutError::Disconnected) => break Err("Import thread stopped unexpectedly".to_string()),
+        }
+      };
+      if let Some(handle) = renewal {
+        let _ = handle.join();
+      }
+      result
+    });
+
+    if temp {
+      if let Err(e) = wipe_file(&import_path) {
+        log::warn!("Failed to wipe decrypted wallet dump: {}", e);
+      }
+    }
+    match result {
+      Ok(_) => emit("complete", Some(1.0), None, true, None),
+      Err(e) => emit("failed", None, None, true, Some(e)),
+    }
+    IMPORT_RUNNING.store(false, Ordering::SeqCst);
+  });
+  Ok(())
+}
@@ -0,0 +1,211 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use chrono::Local;
+use tauri::Emitter;
+
+// Import local modules
+use crate::modules::models::{WalletSession, WalletSessionEvent};
+use crate::modules::commands::{run_cli, wallet_unlocked};
+use crate::modules::files::{ensure_config, load_app_settings};
+
+// The node relocks on its own when `walletpassphrase` times out, but says
+// nothing about it. The session kept here lets the UI count down, warns
+// before the relock and locks early on idle, minimize or leaving the
+// wallet screens. Staking-only sessions are meant to stay open and are
+// only ended by their timeout or an explicit lock.
+
+pub const EXPIRY_WARNING_SECS: u64 = 30;
+pub const DEFAULT_IDLE_LOCK_SECS: u64 = 300;
+
+// How often the watcher checks whether the wallet was locked elsewhere (console, other RPC client)
+const EXTERNAL_CHECK_SECS: u64 = 10;
+
+struct UnlockSession {
+  until: u64,
+  staking_only: bool,
+  warned: bool,
+}
+
+static SESSION: Mutex<Option<UnlockSession>> = Mutex::new(None);
+static LAST_ACTIVITY: AtomicU64 = AtomicU64::new(0);
+static WATCHER_RUNNING: AtomicBool = AtomicBool::new(false);
+
+fn now() -> u64 {
+  Local::now().timestamp() as u64
+}
+
+/// Staking-only unlocks exist on nodes that expose `getstakinginfo`
+pub fn staking_supported() -> bool {
+  run_cli(&[String::from("getstakinginfo")]).is_ok()
+}
+
+/// Remaining seconds and staking flag of the current session, for the dashboard
+pub fn session_info() -> Option<(u64, bool)> {
+  let session = SESSION.lock().ok()?;
+  session.as_ref().map(|s| (s.until.saturating_sub(now()), s.staking_only))
+}
+
+fn emit_locked(app_handle: &tauri::AppHandle, reason: &str) {
+  let _ = app_handle.emit("wallet-session-locked", WalletSessionEvent {
+    remaining: 0,
+    reason: Some(reason.to_string()),
+  });
+}
+
+/// Forget the session (and lock the wallet if `lock`). The session is
+/// dropped even when `walletlock` fails, which usually means the node is gone.
+fn end_session(app_handle: &tauri::AppHandle, reason: &str, lock: bool) -> Result<(), String> {
+  let result = if lock {
+    run_cli(&[String::from("walletlock")]).map(|_| ())
+  } else {
+    Ok(())
+  };
+  if let Ok(mut session) = SESSION.lock() {
+    *session = None;
+  }
+  emit_locked(app_handle, reason);
+  result
+}
+
+fn spawn_watcher(app_handle: tauri::AppHandle) {
+  if WATCHER_RUNNING.swap(true, Ordering::SeqCst) {
+    return;
+  }
+  thread::spawn(move || {
+    let mut ticks = 0u64;
+    loop {
+      loop {
+        thread::sleep(Duration::from_secs(1));
+        ticks += 1;
+        let (remaining, staking_only, warn) = {
+          let mut guard = match SESSION.lock() {
+            Ok(g) => g,
+            Err(_) => break,
+          };
+          let session = match guard.as_mut() {
+            Some(s) => s,
+            None => break,
+          };
+          let remaining = session.until.saturating_sub(now());
+          let warn = remaining <= EXPIRY_WARNING_SECS && !session.warned;
+          if warn {
+            session.warned = true;
+          }
+          (remaining, session.staking_only, warn)
+        };
+
+        if remaining == 0 {
+          // The node has already relocked itself
+          let _ = end_session(&app_handle, "timeout", false);
+          break;
+        }
+        if warn {
+          let _ = app_handle.emit("wallet-session-expiring", WalletSessionEvent { remaining, reason: None });
+        }
+        if !staking_only {
+          let idle = load_app_settings()
+            .ok()
+            .and_then(|s| s.auto_lock_idle_secs)
+            .unwrap_or(DEFAULT_IDLE_LOCK_SECS);
+          if idle > 0 && now().saturating_sub(LAST_ACTIVITY.load(Ordering::SeqCst)) >= idle {
+            if let Err(e) = end_session(&app_handle, "idle", true) {
+              log::warn!("Idle wallet lock failed: {}", e);
+            }
+            break;
+          }
+        }
+        if ticks % EXTERNAL_CHECK_SECS == 0 && wallet_unlocked().ok().flatten() == Some(false) {
+          let _ = end_session(&app_handle, "external", false);
+          break;
+        }
+      }
+      WATCHER_RUNNING.store(false, Ordering::SeqCst);
+      // A session started while this thread was on its way out still needs watching
+      if session_info().is_none() || WATCHER_RUNNING.swap(true, Ordering::SeqCst) {
+        break;
+      }
+    }
+  });
+}
+
+/// Unlock for `duration` seconds and start tracking the session.
+/// `staking_only` keeps spending locked on nodes that support it.
+pub fn start_session(app_handle: tauri::AppHandle, password: String, duration: u64, staking_only: bool) -> Result<String, String> {
+  if duration == 0 {
+    return Err("Duration must be at least one second".to_string());
+  }
+  let mut args = vec![String::from("walletpassphrase"), password, duration.to_string()];
+  if staking_only {
+    if !staking_supported() {
+      return Err("This node does not support unlocking for staking only".to_string());
+    }
+    args.push(String::from("true"));
+  }
+  let result = run_cli(&args)?;
+
+  LAST_ACTIVITY.store(now(), Ordering::SeqCst);
+  *SESSION.lock().map_err(|e| e.to_string())? = Some(UnlockSession {
+    until: now() + duration,
+    staking_only,
+    warned: false,
+  });
+  spawn_watcher(app_handle);
+  Ok(result)
+}
+
+/// Lock because of `reason`. Automatic reasons ("minimized", "left_wallet")
+/// honour their settings and leave staking-only sessions alone. Returns
+/// whether the wallet was locked.
+pub fn lock_for(app_handle: &tauri::AppHandle, reason: &str) -> Result<bool, String> {
+  let staking_only = match session_info() {
+    Some((_, staking_only)) => staking_only,
+    None if reason == "manual" => false,
+    None => return Ok(false),
+  };
+  if reason != "manual" {
+    let settings = load_app_settings().unwrap_or_default();
+    let enabled = match reason {
+      "minimized" => settings.lock_on_minimize.unwrap_or(true),
+      "left_wallet" => settings.lock_on_leave_wallet.unwrap_or(true),
+      _ => true,
+    };
+    if !enabled || staking_only {
+      return Ok(false);
+    }
+  }
+  end_session(app_handle, reason, true)?;
+  Ok(true)
+}
+
+#[tauri::command]
+pub fn get_wallet_session() -> Result<WalletSession, String> {
+  Ok(match session_info() {
+    Some((remaining, staking_only)) => WalletSession {
+      unlocked: remaining > 0,
+      until: Some(now() + remaining),
+      remaining,
+      staking_only,
+    },
+    None => WalletSession { unlocked: false, until: None, remaining: 0, staking_only: false },
+  })
+}
+
+/// Called by the UI on user input to push back the idle lock
+#[tauri::command]
+pub fn touch_wallet_session() {
+  LAST_ACTIVITY.store(now(), Ordering::SeqCst);
+}
+
+#[tauri::command]
+pub fn lock_wallet_session(app_handle: tauri::AppHandle, reason: Option<String>) -> Result<bool, String> {
+  ensure_config()?;
+  lock_for(&app_handle, reason.as_deref().unwrap_or("manual"))
+}
+
+#[tauri::command]
+pub fn get_staking_unlock_supported() -> Result<bool, String> {
+  ensure_config()?;
+  Ok(staking_supported())
+}
@@ -0,0 +1,314 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::Local;
+
+// Import local modules
+use crate::modules::models::{WalletBalance, WalletEntry, WalletFileInfo, WalletRegistry};
+use crate::modules::commands::{run_cli, run_cli_for_wallet};
+use crate::modules::files::{data_dir, ensure_config};
+use crate::modules::process::{start_node, stop_node_internal, wait_for_lock_release};
+use crate::modules::wallet_session::{lock_for, session_info};
+
+// The node loads every wallet passed as `-wallet=<file>` at startup; it has
+// no RPC to load one later. Extra wallets are kept in `wallets.json`, added
+// to the daemon arguments in start_node, and wallet RPCs are routed to the
+// active one with `-rpcwallet`. With no extra wallets the node runs exactly
+// as before on wallet.dat.
+
+pub const DEFAULT_WALLET: &str = "wallet.dat";
+const DEFAULT_WALLET_LABEL: &str = "Main";
+const MAX_WALLET_NAME: usize = 64;
+
+fn wallets_path() -> Result<PathBuf, String> {
+  Ok(data_dir()?.join("wallets.json"))
+}
+
+pub fn load_wallet_registry() -> Result<WalletRegistry, String> {
+  let path = wallets_path()?;
+  if !path.exists() {
+    return Ok(WalletRegistry::default());
+  }
+  let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+  let registry: WalletRegistry = serde_json::from_str(&content).unwrap_or_default();
+  Ok(registry)
+}
+
+fn save_wallet_registry(registry: &WalletRegistry) -> Result<(), String> {
+  let path = wallets_path()?;
+  let content = serde_json::to_string_pretty(registry).map_err(|e| e.to_string())?;
+  fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+/// Wallet file name for `name`: `.dat` is appended when missing. Only plain
+/// file names are accepted, the node refuses paths for `-wallet`.
+fn normalize_wallet_name(name: &str) -> Result<String, String> {
+  let name = name.trim();
+  if name.is_empty() {
+    return Err("Wallet name is required".to_string());
+  }
+  let file = if name.ends_with(".dat") { name.to_string() } else { format!("{}.dat", name) };
+  if file.len() > MAX_WALLET_NAME {
+    return Err(format!("Wallet name is too long (max {} characters)", MAX_WALLET_NAME));
+  }
+  if file.starts_with('.') || !file.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.') {
+    return Err("Wallet names may only contain letters, digits, '-', '_' and '.'".to_string());
+  }
+  Ok(file)
+}
+
+fn has_extra_wallets(registry: &WalletRegistry) -> bool {
+  registry.wallets.iter().any(|w| w.name != DEFAULT_WALLET)
+}
+
+fn is_known(registry: &WalletRegistry, name: &str) -> bool {
+  name == DEFAULT_WALLET || registry.wallets.iter().any(|w| w.name == name)
+}
+
+fn wallet_label(registry: &WalletRegistry, name: &str) -> String {
+  registry
+    .wallets
+    .iter()
+    .find(|w| w.name == name)
+    .map(|w| w.label.clone())
+    .unwrap_or_else(|| if name == DEFAULT_WALLET { DEFAULT_WALLET_LABEL.to_string() } else { String::new() })
+}
+
+/// wallet.dat first, then the extra wallets in the order they were added
+fn wallet_names(registry: &WalletRegistry) -> Vec<String> {
+  let mut names = vec![DEFAULT_WALLET.to_string()];
+  names.extend(registry.wallets.iter().filter(|w| w.name != DEFAULT_WALLET).map(|w| w.name.clone()));
+  names
+}
+
+/// File name of the wallet the commander works on
+pub fn active_wallet() -> String {
+  let registry = load_wallet_registry().unwrap_or_default();
+  match registry.active {
+    Some(ref name) if is_known(&registry, name) => name.clone(),
+    _ => DEFAULT_WALLET.to_string(),
+  }
+}
+
+pub fn active_wallet_path() -> Result<PathBuf, String> {
+  Ok(data_dir()?.join(active_wallet()))
+}
+
+/// Wallet for `-rpcwallet`. Only set when the node runs several wallets,
+/// so single-wallet setups keep talking to the default endpoint.
+pub fn rpc_wallet() -> Option<String> {
+  let registry = load_wallet_registry().ok()?;
+  if !has_extra_wallets(&registry) {
+    return None;
+  }
+  Some(match registry.active {
+    Some(ref name) if is_known(&registry, name) => name.clone(),
+    _ => DEFAULT_WALLET.to_string(),
+  })
+}
+
+/// `-wallet` arguments for the daemon. Empty with no extra wallets; once
+/// one is given the node no longer loads wallet.dat on its own.
+pub fn wallet_start_args() -> Vec<String> {
+  let registry = load_wallet_registry().unwrap_or_default();
+  if !has_extra_wallets(&registry) {
+    return Vec::new();
+  }
+  wallet_names(&registry).iter().map(|name| format!("-wallet={}", name)).collect()
+}
+
+fn node_running() -> bool {
+  run_cli(&[String::from("getblockcount")]).is_ok()
+}
+
+/// Wallets the running node has loaded, `None` when it is not reachable
+fn loaded_wallets() -> Option<Vec<String>> {
+  let raw = run_cli(&[String::from("listwallets")]).ok()?;
+  serde_json::from_str::<Vec<String>>(&raw).ok()
+}
+
+/// Restart the node so it picks up a changed wallet list
+fn restart_node() -> Result<(), String> {
+  if !node_running() {
+    return Ok(());
+  }
+  stop_node_internal();
+  wait_for_lock_release(&data_dir()?);
+  start_node()
+}
+
+fn register(name: String, label: String) -> Result<(), String> {
+  let mut registry = load_wallet_registry()?;
+  if is_known(&registry, &name) {
+    return Err(format!("Wallet {} is already loaded", name));
+  }
+  registry.wallets.push(WalletEntry {
+    name,
+    label: label.trim().to_string(),
+    created: Local::now().timestamp() as u64,
+  });
+  save_wallet_registry(&registry)
+}
+
+/// Balance of every loaded wallet, for the dashboard. Empty with a single
+/// wallet, the main balance already covers it.
+pub fn wallet_balances() -> Vec<WalletBalance> {
+  let registry = load_wallet_registry().unwrap_or_default();
+  if !has_extra_wallets(&registry) {
+    return Vec::new();
+  }
+  let loaded = loaded_wallets().unwrap_or_default();
+  let active = active_wallet();
+  let mut balances = Vec::new();
+  for name in wallet_names(&registry) {
+    if !loaded.contains(&name) {
+      continue;
+    }
+    let info = match run_cli_for_wallet(Some(&name), &[String::from("getwalletinfo")])
+      .ok()
+      .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+    {
+      Some(info) => info,
+      None => continue,
+    };
+    let status = match info["unlocked_until"].as_i64() {
+      Some(0) => "LOCKED",
+      Some(_) => "UNLOCKED",
+      None => "UNENCRYPTED",
+    };
+    balances.push(WalletBalance {
+      label: wallet_label(&registry, &name),
+      active: name == active,
+      balance: format!("{:.3}", info["balance"].as_f64().unwrap_or(0.0)),
+      pending: format!("{:.3}", info["unconfirmed_balance"].as_f64().unwrap_or(0.0)),
+      status: status.to_string(),
+      name,
+    });
+  }
+  balances
+}
+
+#[tauri::command]
+pub fn list_wallet_files() -> Result<Vec<WalletFileInfo>, String> {
+  ensure_config()?;
+  let dir = data_dir()?;
+  let registry = load_wallet_registry()?;
+  let active = active_wallet();
+  let loaded = loaded_wallets();
+  Ok(wallet_names(&registry)
+    .into_iter()
+    .map(|name| WalletFileInfo {
+      label: wallet_label(&registry, &name),
+      active: name == active,
+      // A node running a single wallet lists it under its own name
+      loaded: loaded.as_ref().map(|l| l.contains(&name)).unwrap_or(false),
+      file_exists: dir.join(&name).exists(),
+      name,
+    })
+    .collect())
+}
+
+/// Add a new, empty wallet. The node creates the file when it next starts.
+#[tauri::command]
+pub fn create_wallet(name: String, label: String, restart: bool) -> Result<String, String> {
+  ensure_config()?;
+  let name = normalize_wallet_name(&name)?;
+  if data_dir()?.join(&name).exists() {
+    return Err(format!("{} already exists in the data folder, load it instead", name));
+  }
+  register(name.clone(), label)?;
+  if restart {
+    restart_node()?;
+  }
+  Ok(name)
+}
+
+/// Load an existing wallet file. With `path` the file is first copied into
+/// the data folder as `name`; without it `name` must already be there.
+#[tauri::command]
+pub fn load_wallet(name: String, label: String, path: Option<String>, restart: bool) -> Result<String, String> {
+  ensure_config()?;
+  let name = normalize_wallet_name(&name)?;
+  let dest = data_dir()?.join(&name);
+  match path.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+    Some(src) => {
+      if !Path::new(src).exists() {
+        return Err("Wallet file not found.".to_string());
+      }
+      if dest.exists() {
+        return Err(format!("{} already exists in the data folder", name));
+      }
+      fs::copy(src, &dest).map_err(|e| e.to_string())?;
+    }
+    None if !dest.exists() => return Err(format!("{} not found in the data folder", name)),
+    None => {}
+  }
+  register(name.clone(), label)?;
+  if restart {
+    restart_node()?;
+  }
+  Ok(name)
+}
+
+/// Stop loading a wallet. The file stays in the data folder.
+#[tauri::command]
+pub fn unload_wallet(name: String, restart: bool) -> Result<(), String> {
+  ensure_config()?;
+  if name == DEFAULT_WALLET {
+    return Err("The main wallet cannot be unloaded".to_string());
+  }
+  let mut registry = load_wallet_registry()?;
+  let before = registry.wallets.len();
+  registry.wallets.retain(|w| w.name != name);
+  if registry.wallets.len() == before {
+    return Err(format!("Wallet {} is not loaded", name));
+  }
+  if registry.active.as_deref() == Some(name.as_str()) {
+    registry.active = None;
+  }
+  save_wallet_registry(&registry)?;
+  if restart {
+    restart_node()?;
+  }
+  Ok(())
+}
+
+/// Make `name` the wallet all wallet commands go to. An unlocked session of
+/// the previous wallet is locked first.
+#[tauri::command]
+pub fn switch_wallet(app_handle: tauri::AppHandle, name: String) -> Result<String, String> {
+  ensure_config()?;
+  let mut registry = load_wallet_registry()?;
+  if !is_known(&registry, &name) {
+    return Err(format!("Unknown wallet {}", name));
+  }
+  if let Some(loaded) = loaded_wallets() {
+    if has_extra_wallets(&registry) && !loaded.contains(&name) {
+      return Err(format!("{} is not loaded by the node yet, restart the node first", name));
+    }
+  }
+  if name == active_wallet() {
+    return Ok(name);
+  }
+  if session_info().is_some() {
+    let _ = lock_for(&app_handle, "manual");
+  }
+  registry.active = if name == DEFAULT_WALLET { None } else { Some(name.clone()) };
+  save_wallet_registry(&registry)?;
+  Ok(name)
+}
+
+/// Name a wallet by its role, e.g. "Treasury" or "Operational"
+#[tauri::command]
+pub fn set_wallet_label(name: String, label: String) -> Result<(), String> {
+  let mut registry = load_wallet_registry()?;
+  if !is_known(&registry, &name) {
+    return Err(format!("Unknown wallet {}", name));
+  }
+  let label = label.trim().to_string();
+  match registry.wallets.iter_mut().find(|w| w.name == name) {
+    Some(entry) => entry.label = label,
+    // wallet.dat only gets an entry once it is labelled
+    None => registry.wallets.push(WalletEntry { name, label, created: Local::now().timestamp() as u64 }),
+  }
+  save_wallet_registry(&registry)
+}
@@ -0,0 +1,248 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use chrono::Local;
+
+// Import local modules
+use crate::modules::models::{AssetBalance, Portfolio, PortfolioEntry, WatchOnlyEntry, WatchOnlySummary};
+use crate::modules::utils::{to_sats, format_sats};
+use crate::modules::commands::run_cli;
+use crate::modules::files::{data_dir, ensure_config};
+use crate::modules::rescan::rescan_after_import;
+
+pub const BASE_ASSET: &str = "HEMP";
+
+fn watch_only_path() -> Result<PathBuf, String> {
+  Ok(data_dir()?.join("watch_only.json"))
+}
+
+pub fn load_watch_only() -> Result<Vec<WatchOnlyEntry>, String> {
+  let path = watch_only_path()?;
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+  let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+  let entries: Vec<WatchOnlyEntry> = serde_json::from_str(&content).unwrap_or_default();
+  Ok(entries)
+}
+
+fn save_watch_only(entries: &[WatchOnlyEntry]) -> Result<(), String> {
+  let path = watch_only_path()?;
+  let content = serde_json::to_string_pretty(entries).map_err(|e| e.to_string())?;
+  fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+fn is_pubkey(value: &str) -> bool {
+  (value.len() == 66 || value.len() == 130) && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn addresses_for_label(label: &str) -> Vec<String> {
+  run_cli(&[String::from("getaddressesbyaccount"), label.to_string()])
+    .ok()
+    .and_then(|raw| serde_json::from_str::<Vec<String>>(&raw).ok())
+    .unwrap_or_default()
+}
+
+/// Balances per asset (in sats) from the address index, `HEMP` included.
+/// Fails when the node runs without `addressindex=1`.
+pub fn index_balances(addresses: &[String]) -> Result<BTreeMap<String, u64>, String> {
+  let query = serde_json::json!({ "addresses": addresses }).to_string();
+  let raw = run_cli(&[String::from("getaddressbalance"), query, String::from("true")])?;
+  let value: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  let mut balances = BTreeMap::new();
+  match value.as_array() {
+    Some(rows) => {
+      for row in rows {
+        let name = row["assetName"].as_str().unwrap_or(BASE_ASSET).to_string();
+        *balances.entry(name).or_insert(0) += row["balance"].as_u64().unwrap_or(0);
+      }
+    }
+    // Older nodes ignore includeAssets and return a single HEMP object
+    None => {
+      balances.insert(BASE_ASSET.to_string(), value["balance"].as_u64().unwrap_or(0));
+    }
+  }
+  Ok(balances)
+}
+
+/// HEMP held by watch-only addresses imported into the wallet
+fn wallet_balance(addresses: &[String]) -> Result<u64, String> {
+  let filter = serde_json::to_string(addresses).map_err(|e| e.to_string())?;
+  let raw = run_cli(&[
+    String::from("listunspent"),
+    String::from("0"),
+    String::from("9999999"),
+    filter,
+  ])?;
+  let utxos: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+  Ok(utxos
+    .as_array()
+    .map(|arr| arr.iter().map(|u| to_sats(u["amount"].as_f64().unwrap_or(0.0))).sum())
+    .unwrap_or(0))
+}
+
+fn split_balances(balances: &BTreeMap<String, u64>) -> (u64, Vec<AssetBalance>) {
+  let hemp = balances.get(BASE_ASSET).copied().unwrap_or(0);
+  let assets = balances
+    .iter()
+    .filter(|(name, bal)| name.as_str() != BASE_ASSET && **bal > 0)
+    .map(|(name, bal)| AssetBalance { name: name.clone(), balance: format_sats(*bal) })
+    .collect();
+  (hemp, assets)
+}
+
+/// Add an address or pubkey to watch. With `import` the key goes into the
+/// wallet (`importaddress` / `importpubkey`, rescanning from `rescan_from`
+/// in the background), otherwise it is only tracked here and queried
+/// through the address index.
+#[tauri::command]
+pub fn add_watch_only(
+  app_handle: tauri::AppHandle,
+  key: String,
+  label: String,
+  import: bool,
+  rescan: bool,
+  rescan_from: Option<u64>,
+) -> Result<WatchOnlyEntry, String> {
+  ensure_config()?;
+  let key = key.trim().to_string();
+  if key.is_empty() {
+    return Err("Address or pubkey is required".to_string());
+  }
+  let label = if label.trim().is_empty() { String::from("watch-only") } else { label.trim().to_string() };
+
+  let (address, pubkey) = if is_pubkey(&key) {
+    if !import {
+      return Err("Pubkeys can only be watched by importing them into the wallet".to_string());
+    }
+    let before = addresses_for_label(&label);
+    run_cli(&[String::from("importpubkey"), key.clone(), label.clone(), String::from("false")])?;
+    let address = addresses_for_label(&label)
+      .into_iter()
+      .find(|a| !before.contains(a))
+      .ok_or("Pubkey imported, but its address could not be determined")?;
+    (address, Some(key))
+  } else {
+    let raw = run_cli(&[String::from("validateaddress"), key.clone()])?;
+    let info: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+    if !info["isvalid"].as_bool().unwrap_or(false) {
+      return Err(format!("Invalid address: {}", key));
+    }
+    if import {
+      run_cli(&[
+        String::from("importaddress"),
+        key.clone(),
+        label.clone(),
+        String::from("false"),
+      ])?;
+    } else {
+      index_balances(std::slice::from_ref(&key))
+        .map_err(|e| format!("Address index unavailable (set addressindex=1 or import instead): {}", e))?;
+    }
+    (key, None)
+  };
+  if import {
+    rescan_after_import(app_handle, rescan, rescan_from);
+  }
+
+  let entry = WatchOnlyEntry {
+    label,
+    address: address.clone(),
+    pubkey,
+    imported: import,
+    date: Local::now().timestamp() as u64,
+  };
+  let mut entries = load_watch_only()?;
+  entries.retain(|e| e.address != address);
+  entries.push(entry.clone());
+  save_watch_only(&entries)?;
+  Ok(entry)
+}
+
+/// Stops tracking in the commander. Imported scripts stay in the wallet,
+/// the node has no RPC to remove them.
+#[tauri::command]
+pub fn remove_watch_only(address: String) -> Result<(), String> {
+  let mut entries = load_watch_only()?;
+  entries.retain(|e| e.address != address);
+  save_watch_only(&entries)
+}
+
+#[tauri::command]
+pub fn list_watch_only() -> Result<Vec<WatchOnlyEntry>, String> {
+  load_watch_only()
+}
+
+#[tauri::command]
+pub fn get_portfolio() -> Result<Portfolio, String> {
+  ensure_config()?;
+  let mut entries = Vec::new();
+  let mut total_hemp = 0u64;
+  let mut totals: BTreeMap<String, u64> = BTreeMap::new();
+
+  for watch in load_watch_only()? {
+    let (hemp, assets, source, error) = match index_balances(std::slice::from_ref(&watch.address)) {
+      Ok(balances) => {
+        for (name, bal) in balances.iter().filter(|(name, _)| name.as_str() != BASE_ASSET) {
+          *totals.entry(name.clone()).or_insert(0) += bal;
+        }
+        let (hemp, assets) = split_balances(&balances);
+        (hemp, assets, "index", None)
+      }
+      Err(e) if watch.imported => match wallet_balance(std::slice::from_ref(&watch.address)) {
+        Ok(hemp) => (hemp, Vec::new(), "wallet", None),
+        Err(e2) => (0, Vec::new(), "wallet", Some(format!("{}; {}", e, e2))),
+      },
+      Err(e) => (0, Vec::new(), "index", Some(e)),
+    };
+    total_hemp += hemp;
+    entries.push(PortfolioEntry {
+      label: watch.label,
+      address: watch.address,
+      hemp: format_sats(hemp),
+      assets,
+      source: source.to_string(),
+      error,
+    });
+  }
+
+  let assets: Vec<AssetBalance> = totals
+    .into_iter()
+    .filter(|(_, bal)| *bal > 0)
+    .map(|(name, bal)| AssetBalance { name, balance: format_sats(bal) })
+    .collect();
+
+  Ok(Portfolio {
+    entries,
+    total_hemp: format_sats(total_hemp),
+    assets,
+  })
+}
+
+/// Cheap total for the dashboard: one address-index query for everything.
+/// Without the index only the HEMP on imported entries can be counted, from
+/// the wallet's UTXOs of those addresses, so other watch-only scripts in
+/// the wallet (e.g. multisig) are left out.
+pub fn watch_only_summary() -> Option<WatchOnlySummary> {
+  let entries = load_watch_only().ok()?;
+  if entries.is_empty() {
+    return None;
+  }
+  let addresses: Vec<String> = entries.iter().map(|e| e.address.clone()).collect();
+  let (hemp, assets) = match index_balances(&addresses) {
+    Ok(balances) => split_balances(&balances),
+    Err(_) => {
+      let imported: Vec<String> = entries.iter().filter(|e| e.imported).map(|e| e.address.clone()).collect();
+      if imported.is_empty() {
+        (0, Vec::new())
+      } else {
+        (wallet_balance(&imported).ok()?, Vec::new())
+      }
+    }
+  };
+  Some(WatchOnlySummary {
+    count: entries.len() as u64,
+    balance: format_sats(hemp),
+    assets,
+  })
+}